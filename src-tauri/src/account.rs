@@ -0,0 +1,347 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::validation::{self, ValidationFailure};
+use crate::{cookies, AuthSession, HttpClient, LoonieError};
+
+const PROFILE_URL: &str =
+    "https://services.radio-canada.ca/ott/subscription/v2/gem/subscriber/profile?device=web";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionTier {
+    Free,
+    Member,
+    Premium,
+}
+
+impl SubscriptionTier {
+    /// Maps CBC's tier labels. Anything unrecognised on a signed-in account
+    /// is at least a free membership.
+    fn from_label(label: &str) -> Self {
+        match label.to_ascii_lowercase().as_str() {
+            "premium" | "paid" => Self::Premium,
+            "free" | "anonymous" => Self::Free,
+            _ => Self::Member,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AccountProfile {
+    pub user_id: Option<String>,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub tier: SubscriptionTier,
+}
+
+/// Claims we read from the identity token CBC stores in a cookie. The token
+/// is only decoded, not verified: it is our own session, and the server
+/// checks it on every request anyway.
+#[derive(Debug, Deserialize)]
+struct IdentityClaims {
+    #[serde(alias = "oid")]
+    sub: Option<String>,
+    name: Option<String>,
+    given_name: Option<String>,
+    family_name: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    emails: Vec<String>,
+    tier: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileResponse {
+    #[serde(alias = "userId", alias = "id")]
+    user_id: Option<String>,
+    #[serde(alias = "displayName", alias = "name")]
+    display_name: Option<String>,
+    email: Option<String>,
+    tier: Option<String>,
+    #[serde(rename = "isPremium", default)]
+    is_premium: bool,
+}
+
+fn decode_jwt_claims(token: &str) -> Option<IdentityClaims> {
+    let mut parts = token.split('.');
+    let (_header, payload, _signature) = (parts.next()?, parts.next()?, parts.next()?);
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Builds a profile from the first auth cookie holding an identity token,
+/// in [`cookies::AUTH_COOKIE_NAMES`] order.
+pub fn profile_from_cookies(session: &AuthSession) -> Option<AccountProfile> {
+    let claims = cookies::AUTH_COOKIE_NAMES
+        .iter()
+        .filter_map(|name| session.cookies.get(*name))
+        .filter_map(|value| decode_jwt_claims(value))
+        .find(|claims| claims.sub.is_some())?;
+
+    let display_name = claims.name.or_else(|| {
+        match (claims.given_name.as_deref(), claims.family_name.as_deref()) {
+            (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+            (Some(given), None) => Some(given.to_string()),
+            _ => None,
+        }
+    });
+
+    Some(AccountProfile {
+        user_id: claims.sub,
+        display_name,
+        email: claims.email.or_else(|| claims.emails.into_iter().next()),
+        tier: claims
+            .tier
+            .as_deref()
+            .map(SubscriptionTier::from_label)
+            .unwrap_or(SubscriptionTier::Member),
+    })
+}
+
+/// Fills gaps in `profile` with the profile endpoint's answer. The endpoint
+/// is authoritative for the tier.
+fn merge_profile(profile: Option<AccountProfile>, response: ProfileResponse) -> AccountProfile {
+    let tier = if response.is_premium {
+        SubscriptionTier::Premium
+    } else {
+        response
+            .tier
+            .as_deref()
+            .map(SubscriptionTier::from_label)
+            .or(profile.as_ref().map(|p| p.tier))
+            .unwrap_or(SubscriptionTier::Member)
+    };
+
+    let profile = profile.unwrap_or(AccountProfile {
+        user_id: None,
+        display_name: None,
+        email: None,
+        tier,
+    });
+
+    AccountProfile {
+        user_id: profile.user_id.or(response.user_id),
+        display_name: profile.display_name.or(response.display_name),
+        email: profile.email.or(response.email),
+        tier,
+    }
+}
+
+async fn fetch_profile(
+    http: &HttpClient,
+    session: &mut AuthSession,
+) -> reqwest::Result<reqwest::Response> {
+    let request = http
+        .client()
+        .get(PROFILE_URL)
+        .header("Accept", "application/json");
+    http.send_with(session, request).await
+}
+
+/// Resolves who is signed in from the identity cookie, then asks the
+/// profile endpoint for the subscription tier. Cookies the endpoint
+/// refreshes are applied to `session`.
+pub async fn resolve_profile(
+    http: &HttpClient,
+    session: &mut AuthSession,
+) -> Option<AccountProfile> {
+    let from_cookies = profile_from_cookies(session);
+
+    let response = fetch_profile(http, session)
+        .await
+        .ok()
+        .filter(|r| r.status().is_success());
+
+    let parsed = match response {
+        Some(response) => response.json::<ProfileResponse>().await.ok(),
+        None => None,
+    };
+
+    match parsed {
+        Some(parsed) => Some(merge_profile(from_cookies, parsed)),
+        None => from_cookies,
+    }
+}
+
+/// Just the outcome of a media validation request.
+#[derive(Debug, Deserialize)]
+struct ValidationStatus {
+    #[serde(rename = "errorCode")]
+    error_code: i32,
+    message: Option<String>,
+}
+
+/// Checks that CBC accepts a session obtained outside the auth webview. The
+/// profile endpoint has to answer, and when there is a stream to ask about,
+/// `probe_media`, the validation API must not want a login to play it.
+pub async fn verify_session(
+    http: &HttpClient,
+    session: &mut AuthSession,
+    probe_media: Option<i64>,
+) -> Result<(), LoonieError> {
+    let response = fetch_profile(http, session).await?;
+    check_status(response.status(), "Profile")?;
+
+    let Some(id_media) = probe_media else {
+        return Ok(());
+    };
+    let request = http
+        .client()
+        .get(validation::validation_url(id_media))
+        .header("Accept", "application/json");
+    let response = http.send_with(session, request).await?;
+    check_status(response.status(), "Validation")?;
+
+    let status: ValidationStatus = response
+        .json()
+        .await
+        .map_err(|e| LoonieError::parse("validation response", e))?;
+    let failure = (status.error_code != 0)
+        .then(|| ValidationFailure::classify(status.error_code, status.message.as_deref()));
+    match failure {
+        Some(ValidationFailure::LoginRequired) => Err(LoonieError::NotAuthenticated),
+        // Geo-blocks, tiers and schedules are about the stream, not the session
+        _ => Ok(()),
+    }
+}
+
+fn check_status(status: reqwest::StatusCode, api: &str) -> Result<(), LoonieError> {
+    match status {
+        status if status.is_success() => Ok(()),
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+            Err(LoonieError::NotAuthenticated)
+        }
+        status => Err(LoonieError::Network {
+            message: format!("{} API returned status: {}", api, status),
+            status: Some(status.as_u16()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn jwt(claims: &str) -> String {
+        format!(
+            "{}.{}.signature",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256"}"#),
+            URL_SAFE_NO_PAD.encode(claims)
+        )
+    }
+
+    fn session_with(cookies: &[(&str, String)]) -> AuthSession {
+        AuthSession {
+            cookies: cookies
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect::<HashMap<_, _>>(),
+            cookie_meta: vec![],
+            user_id: None,
+            profile: None,
+            expires_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_profile_from_identity_cookie() {
+        let session = session_with(&[
+            ("tracking", "abc".to_string()),
+            (
+                "login_token",
+                jwt(
+                    r#"{"sub":"user-42","name":"Jane Doe","email":"jane@example.com","tier":"Premium"}"#,
+                ),
+            ),
+        ]);
+
+        let profile = profile_from_cookies(&session).unwrap();
+        assert_eq!(profile.user_id, Some("user-42".to_string()));
+        assert_eq!(profile.display_name, Some("Jane Doe".to_string()));
+        assert_eq!(profile.email, Some("jane@example.com".to_string()));
+        assert_eq!(profile.tier, SubscriptionTier::Premium);
+    }
+
+    #[test]
+    fn test_profile_from_b2c_style_claims() {
+        let session = session_with(&[(
+            "rc_access_token",
+            jwt(
+                r#"{"oid":"abc-123","given_name":"Jean","family_name":"Tremblay","emails":["jean@example.ca"]}"#,
+            ),
+        )]);
+
+        let profile = profile_from_cookies(&session).unwrap();
+        assert_eq!(profile.user_id, Some("abc-123".to_string()));
+        assert_eq!(profile.display_name, Some("Jean Tremblay".to_string()));
+        assert_eq!(profile.email, Some("jean@example.ca".to_string()));
+        assert_eq!(profile.tier, SubscriptionTier::Member);
+    }
+
+    #[test]
+    fn test_profile_from_cookies_prefers_the_login_token() {
+        let session = session_with(&[
+            ("rc_access_token", jwt(r#"{"sub":"from-access-token"}"#)),
+            ("login_token", jwt(r#"{"sub":"from-login-token"}"#)),
+            ("other_jwt", jwt(r#"{"sub":"from-other"}"#)),
+        ]);
+
+        for _ in 0..10 {
+            let profile = profile_from_cookies(&session).unwrap();
+            assert_eq!(profile.user_id, Some("from-login-token".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_profile_from_cookies_without_token() {
+        let session = session_with(&[("sid", "plain-value".to_string())]);
+        assert!(profile_from_cookies(&session).is_none());
+    }
+
+    #[test]
+    fn test_merge_profile_endpoint_sets_tier() {
+        let from_cookies = AccountProfile {
+            user_id: Some("user-42".to_string()),
+            display_name: None,
+            email: Some("jane@example.com".to_string()),
+            tier: SubscriptionTier::Member,
+        };
+        let response: ProfileResponse =
+            serde_json::from_str(r#"{"userId":"other","displayName":"Jane","tier":"Premium"}"#)
+                .unwrap();
+
+        let profile = merge_profile(Some(from_cookies), response);
+        assert_eq!(profile.user_id, Some("user-42".to_string()));
+        assert_eq!(profile.display_name, Some("Jane".to_string()));
+        assert_eq!(profile.email, Some("jane@example.com".to_string()));
+        assert_eq!(profile.tier, SubscriptionTier::Premium);
+    }
+
+    #[test]
+    fn test_merge_profile_is_premium_flag() {
+        let response: ProfileResponse =
+            serde_json::from_str(r#"{"id":"u1","isPremium":true}"#).unwrap();
+
+        let profile = merge_profile(None, response);
+        assert_eq!(profile.user_id, Some("u1".to_string()));
+        assert_eq!(profile.tier, SubscriptionTier::Premium);
+    }
+
+    #[test]
+    fn test_subscription_tier_labels() {
+        assert_eq!(
+            SubscriptionTier::from_label("PREMIUM"),
+            SubscriptionTier::Premium
+        );
+        assert_eq!(SubscriptionTier::from_label("Free"), SubscriptionTier::Free);
+        assert_eq!(
+            SubscriptionTier::from_label("Member"),
+            SubscriptionTier::Member
+        );
+        assert_eq!(
+            SubscriptionTier::from_label("registered"),
+            SubscriptionTier::Member
+        );
+    }
+}
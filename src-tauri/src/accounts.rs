@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{AuthSession, LoonieError, SubscriptionTier};
+
+/// The signed-in CBC accounts, keyed by a user-facing name, with at most one
+/// active account. Requests use the active account unless a viewport pins
+/// another one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Accounts {
+    sessions: BTreeMap<String, AuthSession>,
+    active: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AccountSummary {
+    pub name: String,
+    pub user_id: Option<String>,
+    pub display_name: Option<String>,
+    pub tier: Option<SubscriptionTier>,
+    pub expires_at: i64,
+    pub active: bool,
+}
+
+impl Accounts {
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn active_name(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    pub fn active(&self) -> Option<&AuthSession> {
+        self.active
+            .as_ref()
+            .and_then(|name| self.sessions.get(name))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AuthSession> {
+        self.sessions.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut AuthSession> {
+        self.sessions.get_mut(name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.sessions.keys().cloned().collect()
+    }
+
+    /// Adds or replaces an account and makes it active, returning the name
+    /// it was stored under. Signing in again as a user we already have
+    /// replaces that entry instead of duplicating it; a name already taken by
+    /// a different user gets a number appended rather than overwriting them.
+    pub fn insert(&mut self, name: String, session: AuthSession) -> String {
+        if let Some(user_id) = session.user_id.as_deref() {
+            let existing = self
+                .sessions
+                .iter()
+                .find(|(n, s)| **n != name && s.user_id.as_deref() == Some(user_id))
+                .map(|(n, _)| n.clone());
+            if let Some(existing) = existing {
+                self.sessions.remove(&existing);
+            }
+        }
+
+        let taken_by_other = |name: &str| {
+            self.sessions
+                .get(name)
+                .is_some_and(|s| s.user_id != session.user_id)
+        };
+        let name = if taken_by_other(&name) {
+            (2..)
+                .map(|n| format!("{} ({})", name, n))
+                .find(|candidate| !taken_by_other(candidate))
+                .unwrap()
+        } else {
+            name
+        };
+
+        self.sessions.insert(name.clone(), session);
+        self.active = Some(name.clone());
+        name
+    }
+
+    pub fn switch(&mut self, name: &str) -> Result<(), LoonieError> {
+        if !self.sessions.contains_key(name) {
+            return Err(LoonieError::UnknownAccount {
+                name: name.to_string(),
+            });
+        }
+        self.active = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Removes an account. If it was active, the first remaining account
+    /// takes over.
+    pub fn remove(&mut self, name: &str) -> Option<AuthSession> {
+        let removed = self.sessions.remove(name);
+        if self.active.as_deref() == Some(name) {
+            self.active = self.sessions.keys().next().cloned();
+        }
+        removed
+    }
+
+    /// Drops every expired session, returning the names removed.
+    pub fn remove_expired(&mut self, now: i64) -> Vec<String> {
+        let expired: Vec<String> = self
+            .sessions
+            .iter()
+            .filter(|(_, s)| s.is_expired(now))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in &expired {
+            self.remove(name);
+        }
+        expired
+    }
+
+    pub fn summaries(&self) -> Vec<AccountSummary> {
+        self.sessions
+            .iter()
+            .map(|(name, session)| AccountSummary {
+                name: name.clone(),
+                user_id: session.user_id.clone(),
+                display_name: session
+                    .profile
+                    .as_ref()
+                    .and_then(|p| p.display_name.clone()),
+                tier: session.profile.as_ref().map(|p| p.tier),
+                expires_at: session.expires_at,
+                active: self.active.as_deref() == Some(name.as_str()),
+            })
+            .collect()
+    }
+
+    /// Name for a freshly signed-in account when the user did not pick one.
+    pub fn default_name(&self, session: &AuthSession) -> String {
+        let from_profile = session.profile.as_ref().and_then(|p| {
+            p.display_name
+                .clone()
+                .or_else(|| p.email.clone())
+                .or_else(|| p.user_id.clone())
+        });
+        if let Some(name) = from_profile.or_else(|| session.user_id.clone()) {
+            return name;
+        }
+
+        (1..)
+            .map(|n| format!("Account {}", n))
+            .find(|name| !self.sessions.contains_key(name))
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AccountProfile;
+    use std::collections::HashMap;
+
+    fn session(user_id: Option<&str>, expires_at: i64) -> AuthSession {
+        AuthSession {
+            cookies: HashMap::from([("sid".to_string(), "abc".to_string())]),
+            cookie_meta: vec![],
+            user_id: user_id.map(|s| s.to_string()),
+            profile: None,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn test_insert_makes_account_active() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Home".to_string(), session(Some("u1"), 100));
+        accounts.insert("Office".to_string(), session(Some("u2"), 100));
+
+        assert_eq!(accounts.active_name(), Some("Office"));
+        assert_eq!(accounts.names(), vec!["Home", "Office"]);
+    }
+
+    #[test]
+    fn test_insert_replaces_same_user_under_new_name() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Home".to_string(), session(Some("u1"), 100));
+        accounts.insert("Renamed".to_string(), session(Some("u1"), 200));
+
+        assert_eq!(accounts.names(), vec!["Renamed"]);
+        assert_eq!(accounts.active().unwrap().expires_at, 200);
+    }
+
+    #[test]
+    fn test_insert_keeps_other_user_with_same_name() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Jane".to_string(), session(Some("u1"), 100));
+
+        let name = accounts.insert("Jane".to_string(), session(Some("u2"), 200));
+
+        assert_eq!(name, "Jane (2)");
+        assert_eq!(accounts.names(), vec!["Jane", "Jane (2)"]);
+        assert_eq!(accounts.get("Jane").unwrap().expires_at, 100);
+        assert_eq!(accounts.active_name(), Some("Jane (2)"));
+
+        // The same user signing in again under that name still replaces it
+        accounts.insert("Jane".to_string(), session(Some("u1"), 300));
+        assert_eq!(accounts.get("Jane").unwrap().expires_at, 300);
+    }
+
+    #[test]
+    fn test_switch_account() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Home".to_string(), session(Some("u1"), 100));
+        accounts.insert("Office".to_string(), session(Some("u2"), 100));
+
+        accounts.switch("Home").unwrap();
+        assert_eq!(accounts.active_name(), Some("Home"));
+        assert!(accounts.switch("Cottage").is_err());
+        assert_eq!(accounts.active_name(), Some("Home"));
+    }
+
+    #[test]
+    fn test_remove_active_account_falls_back() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Home".to_string(), session(Some("u1"), 100));
+        accounts.insert("Office".to_string(), session(Some("u2"), 100));
+
+        assert!(accounts.remove("Office").is_some());
+        assert_eq!(accounts.active_name(), Some("Home"));
+
+        assert!(accounts.remove("Home").is_some());
+        assert!(accounts.active_name().is_none());
+        assert!(accounts.is_empty());
+    }
+
+    #[test]
+    fn test_remove_inactive_account_keeps_active() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Home".to_string(), session(Some("u1"), 100));
+        accounts.insert("Office".to_string(), session(Some("u2"), 100));
+
+        accounts.remove("Home");
+        assert_eq!(accounts.active_name(), Some("Office"));
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Old".to_string(), session(Some("u1"), 50));
+        accounts.insert("Fresh".to_string(), session(Some("u2"), 500));
+        accounts.switch("Old").unwrap();
+
+        let expired = accounts.remove_expired(100);
+
+        assert_eq!(expired, vec!["Old"]);
+        assert_eq!(accounts.active_name(), Some("Fresh"));
+    }
+
+    #[test]
+    fn test_summaries_do_not_expose_cookies() {
+        let mut accounts = Accounts::default();
+        accounts.insert("Home".to_string(), session(Some("u1"), 100));
+
+        let summaries = accounts.summaries();
+        let json = serde_json::to_string(&summaries).unwrap();
+
+        assert_eq!(summaries.len(), 1);
+        assert!(summaries[0].active);
+        assert_eq!(summaries[0].user_id, Some("u1".to_string()));
+        assert!(!json.contains("abc"));
+    }
+
+    #[test]
+    fn test_default_name_prefers_profile() {
+        let accounts = Accounts::default();
+        let mut with_profile = session(Some("u1"), 100);
+        with_profile.profile = Some(AccountProfile {
+            user_id: Some("u1".to_string()),
+            display_name: None,
+            email: Some("jane@example.com".to_string()),
+            tier: SubscriptionTier::Member,
+        });
+
+        assert_eq!(accounts.default_name(&with_profile), "jane@example.com");
+    }
+
+    #[test]
+    fn test_default_name_numbers_anonymous_accounts() {
+        let mut accounts = Accounts::default();
+        assert_eq!(accounts.default_name(&session(None, 100)), "Account 1");
+
+        accounts.insert("Account 1".to_string(), session(None, 100));
+        assert_eq!(accounts.default_name(&session(None, 100)), "Account 2");
+    }
+}
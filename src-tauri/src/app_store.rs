@@ -0,0 +1,34 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::LoonieError;
+
+/// `key` from the store in `file`, or `None` when the store can't be opened
+/// or the entry is missing or no longer parses.
+pub fn load<R: Runtime, T: DeserializeOwned>(
+    app: &AppHandle<R>,
+    file: &str,
+    key: &str,
+) -> Option<T> {
+    let value = app.store(file).ok()?.get(key)?;
+    serde_json::from_value(value).ok()
+}
+
+/// Sets `key` in the store in `file` and writes the store to disk.
+pub fn save<R: Runtime, T: Serialize + ?Sized>(
+    app: &AppHandle<R>,
+    file: &str,
+    key: &str,
+    value: &T,
+) -> Result<(), LoonieError> {
+    let value = serde_json::to_value(value).map_err(LoonieError::storage)?;
+    let store = app
+        .store(file)
+        .map_err(|e| LoonieError::storage(format!("Failed to open {}: {}", file, e)))?;
+    store.set(key, value);
+    store
+        .save()
+        .map_err(|e| LoonieError::storage(format!("Failed to save {}: {}", file, e)))
+}
@@ -0,0 +1,284 @@
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Url};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::{
+    account, cookies, session_store, unix_now, AuthSession, AuthState, HttpClient, SessionSummary,
+};
+
+pub const AUTH_URL: &str = "https://www.cbc.ca/account/login?returnto=https%3A%2F%2Fwww.cbc.ca%2F&referrer=https%3A%2F%2Fwww.cbc.ca%2F";
+const LOGIN_URL: &str = "https://www.cbc.ca/account/login";
+const LANDING_URL: &str = "https://www.cbc.ca/account/landing";
+pub const LOGOUT_URL: &str = "https://www.cbc.ca/account/logout";
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Steps of the sign-in flow, emitted as `cbc-auth-progress`.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthFlowState {
+    Opened,
+    CredentialsSubmitted,
+    Redirected,
+    CookiesCaptured,
+    Failed,
+}
+
+impl AuthFlowState {
+    fn is_terminal(self) -> bool {
+        matches!(self, Self::CookiesCaptured | Self::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthProgressPayload {
+    pub state: AuthFlowState,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoggedOutPayload {
+    pub account: String,
+}
+
+/// What the auth webview reports back through its callbacks.
+#[derive(Debug)]
+pub enum AuthFlowEvent {
+    Navigated(Url),
+    PageLoaded(Url),
+    WindowClosed,
+}
+
+#[derive(Debug, PartialEq)]
+enum Step {
+    Navigated,
+    PageLoaded,
+}
+
+fn is_landing(url: &Url) -> bool {
+    url.as_str().starts_with(LANDING_URL)
+}
+
+fn is_login_page(url: &Url) -> bool {
+    url.as_str().starts_with(LOGIN_URL)
+}
+
+/// Advances the flow for a navigation or page load. Leaving the login page
+/// for anything but the landing page means the form was posted.
+fn transition(state: AuthFlowState, step: Step, url: &Url) -> AuthFlowState {
+    if state.is_terminal() || state == AuthFlowState::Redirected {
+        return state;
+    }
+
+    if is_landing(url) {
+        return AuthFlowState::Redirected;
+    }
+
+    match (state, step) {
+        (AuthFlowState::Opened, Step::Navigated) if !is_login_page(url) => {
+            AuthFlowState::CredentialsSubmitted
+        }
+        _ => state,
+    }
+}
+
+fn emit_progress(app: &AppHandle, state: AuthFlowState) {
+    let _ = app.emit("cbc-auth-progress", AuthProgressPayload { state });
+}
+
+fn finish(app: &AppHandle, state: &AuthState, label: &str) {
+    if let Some(window) = app.get_webview_window(label) {
+        let _ = window.close();
+    }
+    *state.auth_webview_label.lock().unwrap() = None;
+}
+
+/// Drives the sign-in flow from the webview callbacks until cookies are
+/// captured, the window is closed or `timeout` elapses.
+pub async fn drive(
+    app: AppHandle,
+    state: AuthState,
+    label: String,
+    account_name: Option<String>,
+    mut events: UnboundedReceiver<AuthFlowEvent>,
+    timeout: Duration,
+) {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut flow = AuthFlowState::Opened;
+    emit_progress(&app, flow);
+
+    loop {
+        let event = match tokio::time::timeout_at(deadline, events.recv()).await {
+            Ok(Some(event)) => event,
+            Ok(None) => return,
+            Err(_) => {
+                finish(&app, &state, &label);
+                emit_progress(&app, AuthFlowState::Failed);
+                let _ = app.emit("cbc-auth-timeout", ());
+                return;
+            }
+        };
+
+        let (step, url) = match event {
+            AuthFlowEvent::WindowClosed => {
+                // Window was closed by user
+                *state.auth_webview_label.lock().unwrap() = None;
+                let _ = app.emit("cbc-auth-cancelled", ());
+                return;
+            }
+            AuthFlowEvent::Navigated(url) => (Step::Navigated, url),
+            AuthFlowEvent::PageLoaded(url) => (Step::PageLoaded, url),
+        };
+
+        let capture = step == Step::PageLoaded && is_landing(&url);
+        let next = transition(flow, step, &url);
+        if next != flow {
+            flow = next;
+            emit_progress(&app, flow);
+        }
+
+        // Cookies are only reliably set once the landing page has loaded
+        if flow == AuthFlowState::Redirected && capture {
+            capture_session(&app, &state, &label, account_name, url).await;
+            return;
+        }
+    }
+}
+
+async fn capture_session(
+    app: &AppHandle,
+    state: &AuthState,
+    label: &str,
+    account_name: Option<String>,
+    url: Url,
+) {
+    let session = app
+        .get_webview_window(label)
+        .and_then(|window| window.cookies_for_url(url.clone()).ok())
+        .and_then(|cookies| cookies::session_from_cookies(&cookies, &url, unix_now()));
+
+    let Some(session) = session else {
+        finish(app, state, label);
+        emit_progress(app, AuthFlowState::Failed);
+        let _ = app.emit("cbc-auth-error", "Failed to extract session cookies");
+        return;
+    };
+
+    let summary = store_session(app, state, account_name, session).await;
+
+    finish(app, state, label);
+    emit_progress(app, AuthFlowState::CookiesCaptured);
+    let _ = app.emit("cbc-auth-success", summary);
+}
+
+/// Resolves who a freshly obtained session belongs to, then adds it as the
+/// active account and persists it.
+pub async fn store_session(
+    app: &AppHandle,
+    state: &AuthState,
+    account_name: Option<String>,
+    mut session: AuthSession,
+) -> SessionSummary {
+    let http = app.state::<HttpClient>();
+    session.profile = account::resolve_profile(&http, &mut session).await;
+    session.user_id = session.profile.as_ref().and_then(|p| p.user_id.clone());
+
+    let mut accounts = state.accounts.lock().unwrap();
+    let name = account_name.unwrap_or_else(|| accounts.default_name(&session));
+    accounts.insert(name, session);
+    if let Err(e) = session_store::persist(app, &accounts) {
+        eprintln!("Failed to persist session: {}", e);
+    }
+    SessionSummary::from_session(accounts.active())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_login_page_reload_stays_opened() {
+        let next = transition(
+            AuthFlowState::Opened,
+            Step::Navigated,
+            &url("https://www.cbc.ca/account/login?step=2"),
+        );
+        assert_eq!(next, AuthFlowState::Opened);
+    }
+
+    #[test]
+    fn test_leaving_login_page_means_credentials_submitted() {
+        let next = transition(
+            AuthFlowState::Opened,
+            Step::Navigated,
+            &url("https://login.cbc.radio-canada.ca/authorize?client=gem"),
+        );
+        assert_eq!(next, AuthFlowState::CredentialsSubmitted);
+    }
+
+    #[test]
+    fn test_page_load_elsewhere_does_not_submit() {
+        let next = transition(
+            AuthFlowState::Opened,
+            Step::PageLoaded,
+            &url("https://login.cbc.radio-canada.ca/authorize"),
+        );
+        assert_eq!(next, AuthFlowState::Opened);
+    }
+
+    #[test]
+    fn test_landing_means_redirected() {
+        let landing = url("https://www.cbc.ca/account/landing?x=1");
+
+        assert_eq!(
+            transition(AuthFlowState::Opened, Step::Navigated, &landing),
+            AuthFlowState::Redirected
+        );
+        assert_eq!(
+            transition(
+                AuthFlowState::CredentialsSubmitted,
+                Step::PageLoaded,
+                &landing
+            ),
+            AuthFlowState::Redirected
+        );
+    }
+
+    #[test]
+    fn test_redirected_is_sticky() {
+        let next = transition(
+            AuthFlowState::Redirected,
+            Step::Navigated,
+            &url("https://www.cbc.ca/"),
+        );
+        assert_eq!(next, AuthFlowState::Redirected);
+    }
+
+    #[test]
+    fn test_terminal_states_do_not_move() {
+        let landing = url("https://www.cbc.ca/account/landing");
+
+        assert_eq!(
+            transition(AuthFlowState::Failed, Step::PageLoaded, &landing),
+            AuthFlowState::Failed
+        );
+        assert_eq!(
+            transition(AuthFlowState::CookiesCaptured, Step::PageLoaded, &landing),
+            AuthFlowState::CookiesCaptured
+        );
+    }
+
+    #[test]
+    fn test_progress_payload_serialization() {
+        let json = serde_json::to_string(&AuthProgressPayload {
+            state: AuthFlowState::CredentialsSubmitted,
+        })
+        .unwrap();
+
+        assert_eq!(json, r#"{"state":"credentials_submitted"}"#);
+    }
+}
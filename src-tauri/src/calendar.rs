@@ -0,0 +1,251 @@
+use chrono::{DateTime, Utc};
+
+use crate::{stream_status, StreamInfo};
+
+/// Scheme registered with the OS so calendar entries can open the app.
+pub const LINK_SCHEME: &str = "loonievision";
+
+const PRODUCT_ID: &str = "-//LoonieVision//Olympic Schedule//EN";
+const CALENDAR_NAME: &str = "LoonieVision Olympics";
+const UID_DOMAIN: &str = "loonievision.app";
+
+/// Content lines longer than this many octets are folded (RFC 5545 §3.1).
+const MAX_LINE_OCTETS: usize = 75;
+
+/// 2024-01-01T00:00:00Z. Event sequence numbers count seconds from here,
+/// which keeps them inside the 32 bits many calendars store them in.
+const SEQUENCE_EPOCH: i64 = 1_704_067_200;
+
+/// Link that opens `stream_id` in the app.
+pub fn stream_link(stream_id: &str) -> String {
+    format!("{}://stream/{}", LINK_SCHEME, stream_id)
+}
+
+/// The stream id in a link made by [`stream_link`].
+pub fn stream_id_from_link(link: &str) -> Option<&str> {
+    let id = link
+        .strip_prefix(LINK_SCHEME)?
+        .strip_prefix("://stream/")?
+        .trim_end_matches('/');
+    (!id.is_empty() && !id.contains('/')).then_some(id)
+}
+
+/// Same for every export of a stream, so calendars that already have the
+/// event update it instead of adding a copy.
+fn event_uid(stream_id: &str) -> String {
+    format!("stream-{}@{}", stream_id, UID_DOMAIN)
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// `SEQUENCE` for an export made at `now`. Calendars only take an updated
+/// event over the copy they have when its sequence is higher, so it grows
+/// with every export.
+fn sequence(now: DateTime<Utc>) -> i64 {
+    (now.timestamp() - SEQUENCE_EPOCH).max(0)
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Appends `line` with CRLF, folding it without splitting a character.
+fn push_line(out: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts too
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+fn push_event(out: &mut String, stream: &StreamInfo, stamp: &str, sequence: i64) -> Option<()> {
+    let start = DateTime::parse_from_rfc3339(&stream.start_time)
+        .ok()?
+        .with_timezone(&Utc);
+    let end = stream
+        .end_time
+        .as_deref()
+        .and_then(|end| DateTime::parse_from_rfc3339(end).ok())
+        .map(|end| end.with_timezone(&Utc))
+        .filter(|end| *end > start)
+        .unwrap_or_else(|| start + stream_status::typical_duration(&stream.sport));
+
+    push_line(out, "BEGIN:VEVENT");
+    push_line(out, &format!("UID:{}", event_uid(&stream.id)));
+    push_line(out, &format!("DTSTAMP:{}", stamp));
+    push_line(out, &format!("LAST-MODIFIED:{}", stamp));
+    push_line(out, &format!("SEQUENCE:{}", sequence));
+    push_line(out, &format!("DTSTART:{}", format_time(start)));
+    push_line(out, &format!("DTEND:{}", format_time(end)));
+    push_line(out, &format!("SUMMARY:{}", escape_text(&stream.title)));
+    push_line(out, &format!("CATEGORIES:{}", escape_text(&stream.sport)));
+    if !stream.description.is_empty() {
+        push_line(
+            out,
+            &format!("DESCRIPTION:{}", escape_text(&stream.description)),
+        );
+    }
+    push_line(out, &format!("URL:{}", stream_link(&stream.id)));
+    push_line(out, "END:VEVENT");
+    Some(())
+}
+
+/// An iCalendar file with one event per stream. Streams without a start
+/// time we can read are left out.
+pub fn to_ics(streams: &[StreamInfo], now: DateTime<Utc>) -> String {
+    let stamp = format_time(now);
+    let sequence = sequence(now);
+    let mut out = String::new();
+
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, &format!("PRODID:{}", PRODUCT_ID));
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, "METHOD:PUBLISH");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", CALENDAR_NAME));
+    for stream in streams {
+        push_event(&mut out, stream, &stamp, sequence);
+    }
+    push_line(&mut out, "END:VCALENDAR");
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(id: &str, start: &str, end: Option<&str>) -> StreamInfo {
+        StreamInfo {
+            title: "Canada vs. Sweden".to_string(),
+            description: "Women's hockey, preliminary round; Group A".to_string(),
+            start_time: start.to_string(),
+            end_time: end.map(str::to_string),
+            ..StreamInfo::test(id)
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-02-01T12:00:00Z".parse().unwrap()
+    }
+
+    fn unfold(ics: &str) -> String {
+        ics.replace("\r\n ", "")
+    }
+
+    #[test]
+    fn test_event_fields() {
+        let ics = to_ics(
+            &[stream(
+                "123",
+                "2026-02-11T14:00:00+01:00",
+                Some("2026-02-11T16:30:00+01:00"),
+            )],
+            now(),
+        );
+        let lines: Vec<_> = ics.split("\r\n").collect();
+
+        assert_eq!(lines[0], "BEGIN:VCALENDAR");
+        for expected in [
+            "UID:stream-123@loonievision.app",
+            "DTSTAMP:20260201T120000Z",
+            "DTSTART:20260211T130000Z",
+            "DTEND:20260211T153000Z",
+            "SUMMARY:Canada vs. Sweden",
+            "CATEGORIES:Hockey",
+            "DESCRIPTION:Women's hockey\\, preliminary round\\; Group A",
+            "URL:loonievision://stream/123",
+        ] {
+            assert!(lines.contains(&expected), "missing {}", expected);
+        }
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_uid_is_stable_across_exports() {
+        let streams = [stream("123", "2026-02-11T14:00:00Z", None)];
+        let first = to_ics(&streams, now());
+        let second = to_ics(&streams, now() + chrono::Duration::days(1));
+
+        let uid = |ics: &str| {
+            ics.lines()
+                .find(|l| l.starts_with("UID:"))
+                .map(str::to_string)
+        };
+        assert_eq!(uid(&first), uid(&second));
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_sequence_grows_with_each_export() {
+        let streams = [stream("123", "2026-02-11T14:00:00Z", None)];
+        let sequence_of = |now| {
+            to_ics(&streams, now)
+                .lines()
+                .find_map(|l| l.strip_prefix("SEQUENCE:"))
+                .map(|s| s.parse::<i64>().unwrap())
+                .unwrap()
+        };
+
+        let first = sequence_of(now());
+        let second = sequence_of(now() + chrono::Duration::minutes(1));
+        assert!(first > 0);
+        assert_eq!(second, first + 60);
+        assert!(second < i64::from(i32::MAX));
+    }
+
+    #[test]
+    fn test_missing_end_uses_typical_duration() {
+        let ics = to_ics(&[stream("1", "2026-02-11T14:00:00Z", None)], now());
+        assert!(ics.contains("DTEND:20260211T170000Z\r\n"));
+    }
+
+    #[test]
+    fn test_unreadable_start_is_skipped() {
+        let ics = to_ics(&[stream("1", "soon", None)], now());
+        assert!(!ics.contains("BEGIN:VEVENT"));
+    }
+
+    #[test]
+    fn test_long_lines_are_folded_on_char_boundaries() {
+        let mut long = stream("1", "2026-02-11T14:00:00Z", None);
+        long.description = "Patinage artistique — épreuve féminine ".repeat(10);
+
+        let ics = to_ics(&[long.clone()], now());
+        assert!(ics.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert!(unfold(&ics).contains(&format!("DESCRIPTION:{}", long.description)));
+    }
+
+    #[test]
+    fn test_stream_links_round_trip() {
+        assert_eq!(
+            stream_id_from_link(&stream_link("abc-123")),
+            Some("abc-123")
+        );
+        assert_eq!(
+            stream_id_from_link("loonievision://stream/abc-123/"),
+            Some("abc-123")
+        );
+        assert_eq!(stream_id_from_link("loonievision://stream/"), None);
+        assert_eq!(stream_id_from_link("https://stream/abc"), None);
+    }
+}
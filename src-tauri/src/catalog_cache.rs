@@ -0,0 +1,230 @@
+use reqwest::header::{
+    HeaderMap, HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{catalog_sections, LoonieError, StreamCatalog};
+
+/// Catalog pages are public and not worth encrypting, so they live in the
+/// cache directory rather than in the session store.
+const CACHE_FILE: &str = "catalog_cache.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CachedPage {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+/// The last catalog pages we received, with their validators, and the page
+/// URLs that made up the last complete snapshot, in order.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct CatalogCache {
+    pages: HashMap<String, CachedPage>,
+    snapshot: Vec<String>,
+    snapshot_at: Option<i64>,
+}
+
+impl CatalogCache {
+    pub fn page(&self, url: &str) -> Option<&CachedPage> {
+        self.pages.get(url)
+    }
+
+    /// `If-None-Match` / `If-Modified-Since` headers to revalidate `url`.
+    pub fn conditional_headers(&self, url: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let Some(page) = self.pages.get(url) else {
+            return headers;
+        };
+        if let Some(value) = page.etag.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = page.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+        headers
+    }
+
+    /// Remembers a fresh `200` response for `url`.
+    pub fn store(&mut self, url: &str, headers: &HeaderMap, body: String) {
+        let header = |name: HeaderName| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        self.pages.insert(
+            url.to_string(),
+            CachedPage {
+                etag: header(ETAG),
+                last_modified: header(LAST_MODIFIED),
+                body,
+            },
+        );
+    }
+
+    /// Marks `urls` as the latest complete catalog and forgets pages that
+    /// are no longer part of it.
+    pub fn set_snapshot(&mut self, urls: Vec<String>, now: i64) {
+        self.pages.retain(|url, _| urls.contains(url));
+        self.snapshot = urls;
+        self.snapshot_at = Some(now);
+    }
+
+    /// URLs and bodies of the last complete catalog, and when it was
+    /// fetched.
+    pub fn snapshot(&self) -> Option<(Vec<(&str, &str)>, i64)> {
+        let fetched_at = self.snapshot_at?;
+        let pages = self
+            .snapshot
+            .iter()
+            .map(|url| {
+                let page = self.pages.get(url)?;
+                Some((url.as_str(), page.body.as_str()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some((pages, fetched_at))
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct CatalogState {
+    pub cache: Arc<Mutex<CatalogCache>>,
+    /// The catalog most recently handed out, for searching without a fetch.
+    pub latest: Arc<Mutex<Option<StreamCatalog>>>,
+    /// Slugs of the GEM sections the catalog is made of.
+    pub sections: Arc<Mutex<Vec<String>>>,
+}
+
+impl CatalogState {
+    pub fn sections(&self) -> Vec<String> {
+        let sections = self.sections.lock().unwrap();
+        if sections.is_empty() {
+            return catalog_sections::default_sections();
+        }
+        sections.clone()
+    }
+}
+
+fn cache_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, LoonieError> {
+    let dir = app.path().app_cache_dir().map_err(|e| {
+        LoonieError::storage(format!("Failed to resolve app cache directory: {}", e))
+    })?;
+    Ok(dir.join(CACHE_FILE))
+}
+
+/// Loads the cached catalog. A missing or unreadable cache is just empty.
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> CatalogCache {
+    cache_path(app)
+        .ok()
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save<R: Runtime>(app: &AppHandle<R>, cache: &CatalogCache) -> Result<(), LoonieError> {
+    let path = cache_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| {
+            LoonieError::storage(format!("Failed to create app cache directory: {}", e))
+        })?;
+    }
+    let bytes = serde_json::to_vec(cache)
+        .map_err(|e| LoonieError::storage(format!("Failed to serialize catalog cache: {}", e)))?;
+
+    // Write then rename, so a crash never leaves a truncated cache behind
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, bytes)
+        .and_then(|_| fs::rename(&tmp, &path))
+        .map_err(|e| LoonieError::storage(format!("Failed to write catalog cache: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    const PAGE_1: &str = "https://example.com/catalog?pageNumber=1";
+    const PAGE_2: &str = "https://example.com/catalog?pageNumber=2";
+
+    fn response_headers(etag: Option<&str>, last_modified: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = etag {
+            headers.insert(ETAG, HeaderValue::from_str(etag).unwrap());
+        }
+        if let Some(last_modified) = last_modified {
+            headers.insert(LAST_MODIFIED, HeaderValue::from_str(last_modified).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_conditional_headers_from_validators() {
+        let mut cache = CatalogCache::default();
+        cache.store(
+            PAGE_1,
+            &response_headers(Some("\"abc\""), Some("Wed, 11 Feb 2026 10:00:00 GMT")),
+            "{}".to_string(),
+        );
+
+        let headers = cache.conditional_headers(PAGE_1);
+        assert_eq!(headers.get(IF_NONE_MATCH).unwrap(), "\"abc\"");
+        assert_eq!(
+            headers.get(IF_MODIFIED_SINCE).unwrap(),
+            "Wed, 11 Feb 2026 10:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn test_no_conditional_headers_without_validators() {
+        let mut cache = CatalogCache::default();
+        assert!(cache.conditional_headers(PAGE_1).is_empty());
+
+        cache.store(PAGE_1, &HeaderMap::new(), "{}".to_string());
+        assert!(cache.conditional_headers(PAGE_1).is_empty());
+        assert_eq!(cache.page(PAGE_1).unwrap().body, "{}");
+    }
+
+    #[test]
+    fn test_snapshot_keeps_page_order_and_drops_old_pages() {
+        let mut cache = CatalogCache::default();
+        assert!(cache.snapshot().is_none());
+
+        cache.store(PAGE_2, &HeaderMap::new(), "two".to_string());
+        cache.store(PAGE_1, &HeaderMap::new(), "one".to_string());
+        cache.store(
+            "https://example.com/old",
+            &HeaderMap::new(),
+            "old".to_string(),
+        );
+        cache.set_snapshot(vec![PAGE_1.to_string(), PAGE_2.to_string()], 1_700_000_000);
+
+        assert_eq!(
+            cache.snapshot(),
+            Some((vec![(PAGE_1, "one"), (PAGE_2, "two")], 1_700_000_000))
+        );
+        assert!(cache.page("https://example.com/old").is_none());
+    }
+
+    #[test]
+    fn test_cache_roundtrip() {
+        let mut cache = CatalogCache::default();
+        cache.store(
+            PAGE_1,
+            &response_headers(Some("W/\"1\""), None),
+            "one".to_string(),
+        );
+        cache.set_snapshot(vec![PAGE_1.to_string()], 42);
+
+        let json = serde_json::to_string(&cache).unwrap();
+        let restored: CatalogCache = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, cache);
+        assert_eq!(restored.snapshot(), Some((vec![(PAGE_1, "one")], 42)));
+    }
+}
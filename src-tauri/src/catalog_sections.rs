@@ -0,0 +1,181 @@
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+
+use crate::{app_store, LoonieError};
+
+const STORE_FILE: &str = "settings.json";
+const SECTIONS_ENTRY: &str = "catalog_sections";
+
+const SECTION_BASE_URL: &str = "https://services.radio-canada.ca/ott/catalog/v2/gem/section";
+
+pub const DEFAULT_SECTION: &str = "olympics";
+
+/// Sections offered to pick from, by slug: the games, the other major
+/// sports sections and the regular live channels. Any other GEM section can
+/// be enabled by its slug.
+const KNOWN_SECTIONS: &[(&str, &str)] = &[
+    ("olympics", "Olympics"),
+    ("paralympics", "Paralympics"),
+    ("sports", "Sports"),
+    ("hockey-night-in-canada", "Hockey Night in Canada"),
+    ("curling", "Curling"),
+    ("figure-skating", "Figure Skating"),
+    ("soccer", "Soccer"),
+    ("athletics", "Athletics"),
+    ("live", "Live"),
+    ("cbc-news-network", "CBC News Network"),
+];
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct CatalogSection {
+    pub slug: String,
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// A section that couldn't be fetched while building the catalog. Its
+/// streams, if any, are the ones from the last complete catalog.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SectionError {
+    pub section: String,
+    pub error: LoonieError,
+}
+
+pub fn default_sections() -> Vec<String> {
+    vec![DEFAULT_SECTION.to_string()]
+}
+
+pub fn section_url(slug: &str) -> String {
+    format!("{}/{}", SECTION_BASE_URL, slug)
+}
+
+/// The section a catalog page URL belongs to.
+pub fn section_of_url(url: &str) -> Option<&str> {
+    let rest = url.strip_prefix(SECTION_BASE_URL)?.strip_prefix('/')?;
+    let slug = rest.split(['?', '/']).next()?;
+    is_valid_slug(slug).then_some(slug)
+}
+
+fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Trimmed, lowercased slugs without duplicates, in the order given. No
+/// slugs at all means the default section.
+pub fn normalize(slugs: &[String]) -> Result<Vec<String>, LoonieError> {
+    let mut sections: Vec<String> = Vec::new();
+    for slug in slugs {
+        let slug = slug.trim().to_lowercase();
+        if !is_valid_slug(&slug) {
+            return Err(LoonieError::InvalidCatalogSection { slug });
+        }
+        if !sections.contains(&slug) {
+            sections.push(slug);
+        }
+    }
+
+    if sections.is_empty() {
+        return Ok(default_sections());
+    }
+    Ok(sections)
+}
+
+/// The known sections, then any other enabled ones.
+pub fn available(enabled: &[String]) -> Vec<CatalogSection> {
+    let known = KNOWN_SECTIONS.iter().map(|(slug, name)| CatalogSection {
+        slug: slug.to_string(),
+        name: name.to_string(),
+        enabled: enabled.iter().any(|s| s == slug),
+    });
+    let custom = enabled
+        .iter()
+        .filter(|slug| !KNOWN_SECTIONS.iter().any(|(known, _)| known == slug))
+        .map(|slug| CatalogSection {
+            slug: slug.clone(),
+            name: slug.clone(),
+            enabled: true,
+        });
+    known.chain(custom).collect()
+}
+
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    app_store::load::<_, Vec<String>>(app, STORE_FILE, SECTIONS_ENTRY)
+        .and_then(|slugs| normalize(&slugs).ok())
+        .unwrap_or_else(default_sections)
+}
+
+pub fn save<R: Runtime>(app: &AppHandle<R>, sections: &[String]) -> Result<(), LoonieError> {
+    app_store::save(app, STORE_FILE, SECTIONS_ENTRY, sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slugs(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(
+            normalize(&slugs(&[" Paralympics", "olympics", "paralympics"])).unwrap(),
+            slugs(&["paralympics", "olympics"])
+        );
+        assert_eq!(normalize(&[]).unwrap(), slugs(&["olympics"]));
+    }
+
+    #[test]
+    fn test_normalize_rejects_malformed_slugs() {
+        for slug in ["", "olympics/../admin", "live?device=web", "été"] {
+            assert_eq!(
+                normalize(&slugs(&[slug])),
+                Err(LoonieError::InvalidCatalogSection {
+                    slug: slug.trim().to_lowercase()
+                }),
+            );
+        }
+    }
+
+    #[test]
+    fn test_available_lists_known_then_custom_sections() {
+        let sections = available(&slugs(&["paralympics", "curling-canada"]));
+        let listed: Vec<_> = sections
+            .iter()
+            .map(|s| (s.slug.as_str(), s.enabled))
+            .collect();
+
+        assert_eq!(listed.len(), KNOWN_SECTIONS.len() + 1);
+        assert_eq!(&listed[..2], [("olympics", false), ("paralympics", true)]);
+        assert_eq!(listed.last(), Some(&("curling-canada", true)));
+        assert_eq!(sections.last().unwrap().name, "curling-canada");
+    }
+
+    #[test]
+    fn test_available_offers_sports_and_live_channels() {
+        let sections = available(&[]);
+        let offered: Vec<_> = sections.iter().map(|s| s.slug.as_str()).collect();
+
+        for slug in [
+            "sports",
+            "hockey-night-in-canada",
+            "curling",
+            "live",
+            "cbc-news-network",
+        ] {
+            assert!(offered.contains(&slug), "{} is not offered", slug);
+        }
+        assert!(sections.iter().all(|s| !s.enabled));
+        assert!(KNOWN_SECTIONS.iter().all(|(slug, _)| is_valid_slug(slug)));
+    }
+
+    #[test]
+    fn test_section_of_url() {
+        let url = format!("{}?device=web&pageNumber=2", section_url("paralympics"));
+        assert_eq!(section_of_url(&url), Some("paralympics"));
+        assert_eq!(section_of_url("https://example.com/section/olympics"), None);
+    }
+}
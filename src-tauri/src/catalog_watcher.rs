@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+use crate::{commands, StreamInfo, StreamStatus};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// Floor for the configurable interval, to stay polite to the catalog API.
+const MIN_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How a stream changed between two catalog snapshots. Each change is
+/// emitted as its own event with the stream as payload.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CatalogChange {
+    Added(StreamInfo),
+    Removed(StreamInfo),
+    WentLive(StreamInfo),
+    Ended(StreamInfo),
+    ReplayAvailable(StreamInfo),
+}
+
+impl CatalogChange {
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::Added(_) => "stream-added",
+            Self::Removed(_) => "stream-removed",
+            Self::WentLive(_) => "stream-went-live",
+            Self::Ended(_) => "stream-ended",
+            Self::ReplayAvailable(_) => "stream-replay-available",
+        }
+    }
+
+    pub fn stream(&self) -> &StreamInfo {
+        match self {
+            Self::Added(stream)
+            | Self::Removed(stream)
+            | Self::WentLive(stream)
+            | Self::Ended(stream)
+            | Self::ReplayAvailable(stream) => stream,
+        }
+    }
+}
+
+/// Changes from `previous` to `current`: removals in their old order, then
+/// additions and transitions in catalog order.
+pub fn diff_catalogs(previous: &[StreamInfo], current: &[StreamInfo]) -> Vec<CatalogChange> {
+    let before: HashMap<&str, &StreamInfo> = previous.iter().map(|s| (s.id.as_str(), s)).collect();
+    let after: HashMap<&str, &StreamInfo> = current.iter().map(|s| (s.id.as_str(), s)).collect();
+
+    let mut changes: Vec<CatalogChange> = previous
+        .iter()
+        .filter(|s| !after.contains_key(s.id.as_str()))
+        .map(|s| CatalogChange::Removed(s.clone()))
+        .collect();
+
+    for stream in current {
+        let Some(old) = before.get(stream.id.as_str()) else {
+            changes.push(CatalogChange::Added(stream.clone()));
+            continue;
+        };
+
+        if old.status != stream.status {
+            match stream.status {
+                StreamStatus::Live => changes.push(CatalogChange::WentLive(stream.clone())),
+                StreamStatus::Ended => changes.push(CatalogChange::Ended(stream.clone())),
+                _ => {}
+            }
+        }
+        if !old.vod_enabled && stream.vod_enabled {
+            changes.push(CatalogChange::ReplayAvailable(stream.clone()));
+        }
+    }
+
+    changes
+}
+
+/// Settings of the background catalog watcher, managed as Tauri state.
+#[derive(Clone)]
+pub struct CatalogWatcher {
+    interval: Arc<Mutex<Duration>>,
+    wake: Arc<Notify>,
+}
+
+impl CatalogWatcher {
+    pub fn interval(&self) -> Duration {
+        *self.interval.lock().unwrap()
+    }
+
+    /// Changes the poll interval and refreshes right away.
+    pub fn set_interval(&self, interval: Duration) {
+        *self.interval.lock().unwrap() = interval.max(MIN_INTERVAL);
+        self.refresh();
+    }
+
+    /// Fetches the catalog now instead of at the next tick.
+    pub fn refresh(&self) {
+        self.wake.notify_one();
+    }
+}
+
+impl Default for CatalogWatcher {
+    fn default() -> Self {
+        Self {
+            interval: Arc::new(Mutex::new(DEFAULT_INTERVAL)),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+}
+
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let watcher = app.state::<CatalogWatcher>().inner().clone();
+        let mut previous: Option<Vec<StreamInfo>> = None;
+
+        loop {
+            match commands::load_catalog(&app).await {
+                // A cached catalog says nothing new; keep diffing against the
+                // last live one
+                Ok(catalog) if catalog.stale => {}
+                Ok(catalog) => {
+                    if let Some(previous) = &previous {
+                        for change in diff_catalogs(previous, &catalog.streams) {
+                            let _ = app.emit(change.event_name(), change.stream());
+                        }
+                    }
+                    previous = Some(catalog.streams);
+                }
+                Err(e) => eprintln!("Catalog watcher failed to fetch the catalog: {}", e),
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(watcher.interval()) => {}
+                _ = watcher.wake.notified() => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(id: &str, status: StreamStatus, vod_enabled: bool) -> StreamInfo {
+        StreamInfo {
+            status,
+            end_time: Some("2026-02-10T17:00:00Z".to_string()),
+            vod_enabled,
+            ..StreamInfo::test(id)
+        }
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let catalog = vec![stream("1", StreamStatus::Live, false)];
+        assert!(diff_catalogs(&catalog, &catalog).is_empty());
+    }
+
+    #[test]
+    fn test_added_and_removed() {
+        let previous = vec![
+            stream("1", StreamStatus::Upcoming, false),
+            stream("2", StreamStatus::Upcoming, false),
+        ];
+        let current = vec![
+            stream("2", StreamStatus::Upcoming, false),
+            stream("3", StreamStatus::Upcoming, false),
+        ];
+
+        assert_eq!(
+            diff_catalogs(&previous, &current),
+            vec![
+                CatalogChange::Removed(previous[0].clone()),
+                CatalogChange::Added(current[1].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        let cases = [
+            (
+                StreamStatus::Upcoming,
+                StreamStatus::Live,
+                Some("stream-went-live"),
+            ),
+            (
+                StreamStatus::Live,
+                StreamStatus::Ended,
+                Some("stream-ended"),
+            ),
+            (
+                StreamStatus::Upcoming,
+                StreamStatus::Ended,
+                Some("stream-ended"),
+            ),
+            (StreamStatus::Ended, StreamStatus::Upcoming, None),
+        ];
+
+        for (before, after, expected) in cases {
+            let changes =
+                diff_catalogs(&[stream("1", before, false)], &[stream("1", after, false)]);
+            let names: Vec<_> = changes.iter().map(|c| c.event_name()).collect();
+            assert_eq!(names, expected.into_iter().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_replay_available_when_vod_flips() {
+        let previous = vec![stream("1", StreamStatus::Ended, false)];
+        let current = vec![stream("1", StreamStatus::Replay, true)];
+
+        assert_eq!(
+            diff_catalogs(&previous, &current),
+            vec![CatalogChange::ReplayAvailable(current[0].clone())]
+        );
+    }
+
+    #[test]
+    fn test_live_stream_gains_replay() {
+        let previous = vec![stream("1", StreamStatus::Upcoming, false)];
+        let current = vec![stream("1", StreamStatus::Live, true)];
+
+        let changes = diff_catalogs(&previous, &current);
+        let names: Vec<_> = changes.iter().map(|c| c.event_name()).collect();
+        assert_eq!(names, vec!["stream-went-live", "stream-replay-available"]);
+        assert_eq!(changes[0].stream(), &current[0]);
+    }
+
+    #[test]
+    fn test_interval_has_a_floor() {
+        let watcher = CatalogWatcher::default();
+        assert_eq!(watcher.interval(), DEFAULT_INTERVAL);
+
+        watcher.set_interval(Duration::from_secs(1));
+        assert_eq!(watcher.interval(), MIN_INTERVAL);
+
+        watcher.set_interval(Duration::from_secs(600));
+        assert_eq!(watcher.interval(), Duration::from_secs(600));
+    }
+}
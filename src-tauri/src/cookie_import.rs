@@ -0,0 +1,404 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{cookies, AuthSession, CookieMeta, LoonieError};
+
+/// Seconds between 1601-01-01, Chromium's epoch, and the Unix epoch.
+const CHROMIUM_EPOCH_OFFSET: i64 = 11_644_473_600;
+
+static IMPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Where to import a CBC session from when the auth webview can't be used.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ImportSource {
+    /// A Netscape `cookies.txt` export.
+    CookiesTxt { path: PathBuf },
+    /// A Firefox profile directory, or its `cookies.sqlite`.
+    Firefox { profile: PathBuf },
+    /// A Chromium-family profile directory, or its `Cookies` database.
+    Chromium { profile: PathBuf },
+}
+
+/// Reads the CBC cookies from `source` into a session.
+pub fn session_from_source(source: &ImportSource, now: i64) -> Result<AuthSession, LoonieError> {
+    let cbc_cookies: Vec<CookieMeta> = read_cookies(source)
+        .map_err(|message| LoonieError::ImportFailed { message })?
+        .into_iter()
+        .filter(|meta| cookies::is_cbc_domain(&meta.domain))
+        .collect();
+
+    cookies::session_from_parts(cbc_cookies, now).ok_or_else(|| LoonieError::ImportFailed {
+        message: "No CBC cookies found to import".to_string(),
+    })
+}
+
+fn read_cookies(source: &ImportSource) -> Result<Vec<CookieMeta>, String> {
+    match source {
+        ImportSource::CookiesTxt { path } => {
+            let contents = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            Ok(parse_cookies_txt(&contents))
+        }
+        ImportSource::Firefox { profile } => {
+            let db = database_path(profile, &["cookies.sqlite"])?;
+            with_database_copy(&db, read_firefox)
+        }
+        ImportSource::Chromium { profile } => {
+            let db = database_path(profile, &["Network/Cookies", "Cookies"])?;
+            with_database_copy(&db, read_chromium)
+        }
+    }
+}
+
+/// Accepts either the database file itself or the profile directory.
+fn database_path(profile: &Path, candidates: &[&str]) -> Result<PathBuf, String> {
+    if profile.is_file() {
+        return Ok(profile.to_path_buf());
+    }
+    candidates
+        .iter()
+        .map(|name| profile.join(name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("No cookie database found in {}", profile.display()))
+}
+
+/// Browsers keep their cookie database locked while running, so it is read
+/// from a copy (with its write-ahead log, which may hold recent cookies).
+fn with_database_copy<T>(
+    db: &Path,
+    read: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+) -> Result<T, String> {
+    let dir = std::env::temp_dir().join(format!(
+        "loonievision-import-{}-{}",
+        std::process::id(),
+        IMPORT_COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp directory: {}", e))?;
+
+    let copy = dir.join("cookies.db");
+    let result = fs::copy(db, &copy)
+        .map_err(|e| format!("Failed to copy {}: {}", db.display(), e))
+        .and_then(|_| {
+            let mut wal = db.as_os_str().to_owned();
+            wal.push("-wal");
+            if Path::new(&wal).is_file() {
+                let _ = fs::copy(&wal, dir.join("cookies.db-wal"));
+            }
+
+            let conn = Connection::open_with_flags(&copy, OpenFlags::SQLITE_OPEN_READ_WRITE)
+                .map_err(|e| format!("Failed to open cookie database: {}", e))?;
+            read(&conn).map_err(|e| format!("Failed to read cookie database: {}", e))
+        });
+
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+/// Parses a Netscape `cookies.txt`: tab-separated domain, subdomain flag,
+/// path, secure flag, expiry and name/value, with `#HttpOnly_` marking
+/// http-only cookies.
+fn parse_cookies_txt(contents: &str) -> Vec<CookieMeta> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(rest) => (rest, true),
+                None => (line, false),
+            };
+            if line.starts_with('#') || line.trim().is_empty() {
+                return None;
+            }
+
+            let fields: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('\t').collect();
+            let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+                return None;
+            };
+            let expires_at = expires.parse::<i64>().ok().filter(|&exp| exp > 0);
+
+            Some(CookieMeta {
+                name: name.to_string(),
+                value: value.to_string(),
+                domain: domain.trim_start_matches('.').to_string(),
+                host_only: !subdomains.eq_ignore_ascii_case("TRUE"),
+                path: path.to_string(),
+                expires_at,
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                http_only,
+            })
+        })
+        .collect()
+}
+
+/// Browsers store a cookie's `Domain` with a leading dot and the host of a
+/// host-only cookie without one.
+fn browser_domain(host: String) -> (String, bool) {
+    match host.strip_prefix('.') {
+        Some(domain) => (domain.to_string(), false),
+        None => (host, true),
+    }
+}
+
+fn read_firefox(conn: &Connection) -> rusqlite::Result<Vec<CookieMeta>> {
+    let mut stmt = conn
+        .prepare("SELECT name, value, host, path, expiry, isSecure, isHttpOnly FROM moz_cookies")?;
+    let rows = stmt.query_map([], |row| {
+        let expiry: i64 = row.get(4)?;
+        let (domain, host_only) = browser_domain(row.get(2)?);
+        Ok(CookieMeta {
+            name: row.get(0)?,
+            value: row.get(1)?,
+            domain,
+            host_only,
+            path: row.get(3)?,
+            expires_at: firefox_expiry(expiry),
+            secure: row.get(5)?,
+            http_only: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Newer Firefox versions store the expiry in milliseconds.
+fn firefox_expiry(expiry: i64) -> Option<i64> {
+    match expiry {
+        exp if exp <= 0 => None,
+        exp if exp > 100_000_000_000 => Some(exp / 1000),
+        exp => Some(exp),
+    }
+}
+
+fn read_chromium(conn: &Connection) -> rusqlite::Result<Vec<CookieMeta>> {
+    // From schema version 24 the decrypted value starts with a hash of the host
+    let version: i64 = conn
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .map(|v| v.parse().unwrap_or(0))
+        .unwrap_or(0);
+
+    let mut stmt = conn.prepare(
+        "SELECT name, value, encrypted_value, host_key, path, expires_utc, is_secure, is_httponly FROM cookies",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let value: String = row.get(1)?;
+        let encrypted: Vec<u8> = row.get(2)?;
+        let value = if value.is_empty() {
+            decrypt_chromium_value(&encrypted, version >= 24).unwrap_or_default()
+        } else {
+            value
+        };
+        let expires_utc: i64 = row.get(5)?;
+        let (domain, host_only) = browser_domain(row.get(3)?);
+
+        Ok(CookieMeta {
+            name: row.get(0)?,
+            value,
+            domain,
+            host_only,
+            path: row.get(4)?,
+            expires_at: chromium_expiry(expires_utc),
+            secure: row.get(6)?,
+            http_only: row.get(7)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Chromium stores expiry as microseconds since 1601; 0 is a session cookie.
+fn chromium_expiry(expires_utc: i64) -> Option<i64> {
+    (expires_utc > 0).then(|| expires_utc / 1_000_000 - CHROMIUM_EPOCH_OFFSET)
+}
+
+/// Key for `v10` values, which Chromium on Linux encrypts with a fixed
+/// password when no keyring is available.
+fn chromium_v10_key() -> [u8; 16] {
+    let mut key = [0u8; 16];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(b"peanuts", b"saltysalt", 1, &mut key);
+    key
+}
+
+/// Decrypts a `v10` cookie value. Values protected by the OS keyring (`v11`,
+/// macOS Keychain, Windows DPAPI) can't be read and come back as `None`.
+fn decrypt_chromium_value(encrypted: &[u8], strip_host_hash: bool) -> Option<String> {
+    let ciphertext = encrypted.strip_prefix(b"v10")?;
+    let mut buf = ciphertext.to_vec();
+    let plaintext =
+        cbc::Decryptor::<aes::Aes128>::new(&chromium_v10_key().into(), &[b' '; 16].into())
+            .decrypt_padded_mut::<Pkcs7>(&mut buf)
+            .ok()?;
+    let plaintext = if strip_host_hash {
+        plaintext.get(32..)?
+    } else {
+        plaintext
+    };
+    String::from_utf8(plaintext.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn encrypt_v10(plaintext: &[u8]) -> Vec<u8> {
+        let mut buf = plaintext.to_vec();
+        buf.resize(plaintext.len() + 16, 0);
+        let ciphertext =
+            cbc::Encryptor::<aes::Aes128>::new(&chromium_v10_key().into(), &[b' '; 16].into())
+                .encrypt_padded_mut::<Pkcs7>(&mut buf, plaintext.len())
+                .unwrap();
+        [b"v10".as_slice(), ciphertext].concat()
+    }
+
+    fn temp_db(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("loonievision-test-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("cookies.sqlite")
+    }
+
+    #[test]
+    fn test_parse_cookies_txt() {
+        let contents = "# Netscape HTTP Cookie File\n\
+            .cbc.ca\tTRUE\t/\tTRUE\t1800000000\tlogin_token\tabc\n\
+            #HttpOnly_.radio-canada.ca\tTRUE\t/\tFALSE\t0\tsid\tdef\n\
+            \n\
+            malformed line\n";
+
+        let cookies = parse_cookies_txt(contents);
+
+        assert_eq!(cookies.len(), 2);
+        let token = &cookies[0];
+        assert_eq!(token.name, "login_token");
+        assert_eq!(token.value, "abc");
+        assert_eq!(token.domain, "cbc.ca");
+        assert!(!token.host_only);
+        assert_eq!(token.expires_at, Some(1_800_000_000));
+        assert!(token.secure);
+        assert!(!token.http_only);
+
+        let sid = &cookies[1];
+        assert!(sid.http_only);
+        assert_eq!(sid.expires_at, None);
+    }
+
+    #[test]
+    fn test_cookies_txt_import_keeps_only_cbc_domains() {
+        let path = temp_db("txt").with_file_name("cookies.txt");
+        fs::write(
+            &path,
+            ".cbc.ca\tTRUE\t/\tTRUE\t1800000000\tlogin_token\tabc\n\
+             .example.com\tTRUE\t/\tFALSE\t1800000000\ttracker\tx\n\
+             .notcbc.ca\tTRUE\t/\tFALSE\t1800000000\tother\ty\n",
+        )
+        .unwrap();
+
+        let session = session_from_source(&ImportSource::CookiesTxt { path }, NOW).unwrap();
+
+        assert_eq!(session.cookies.len(), 1);
+        assert_eq!(session.expires_at, 1_800_000_000);
+    }
+
+    #[test]
+    fn test_import_without_cbc_cookies_fails() {
+        let path = temp_db("empty").with_file_name("cookies.txt");
+        fs::write(&path, ".example.com\tTRUE\t/\tFALSE\t0\ta\tb\n").unwrap();
+
+        assert!(session_from_source(&ImportSource::CookiesTxt { path }, NOW).is_err());
+    }
+
+    #[test]
+    fn test_firefox_profile_import() {
+        let db = temp_db("firefox");
+        let _ = fs::remove_file(&db);
+        let conn = Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_cookies (name TEXT, value TEXT, host TEXT, path TEXT, \
+                 expiry INTEGER, isSecure INTEGER, isHttpOnly INTEGER);
+             INSERT INTO moz_cookies VALUES ('login_token', 'abc', '.cbc.ca', '/', 1800000000, 1, 1);
+             INSERT INTO moz_cookies VALUES ('sid', 'def', 'gem.cbc.ca', '/', 1800000000000, 1, 0);
+             INSERT INTO moz_cookies VALUES ('ad', 'x', '.example.com', '/', 1800000000, 0, 0);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let profile = db.parent().unwrap().to_path_buf();
+        let session = session_from_source(&ImportSource::Firefox { profile }, NOW).unwrap();
+
+        assert_eq!(session.cookies.len(), 2);
+        assert_eq!(session.cookies.get("login_token"), Some(&"abc".to_string()));
+        let sid = session
+            .cookie_meta
+            .iter()
+            .find(|m| m.name == "sid")
+            .unwrap();
+        assert_eq!(sid.expires_at, Some(1_800_000_000));
+        assert_eq!(sid.domain, "gem.cbc.ca");
+        assert!(sid.host_only);
+    }
+
+    #[test]
+    fn test_chromium_database_import() {
+        let db = temp_db("chromium").with_file_name("Cookies");
+        let _ = fs::remove_file(&db);
+        let conn = Connection::open(&db).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE meta (key TEXT, value TEXT);
+             INSERT INTO meta VALUES ('version', '24');
+             CREATE TABLE cookies (name TEXT, value TEXT, encrypted_value BLOB, host_key TEXT, \
+                 path TEXT, expires_utc INTEGER, is_secure INTEGER, is_httponly INTEGER);",
+        )
+        .unwrap();
+        let encrypted = encrypt_v10(&[[0u8; 32].as_slice(), b"abc"].concat());
+        let expires_utc = (1_800_000_000 + CHROMIUM_EPOCH_OFFSET) * 1_000_000;
+        conn.execute(
+            "INSERT INTO cookies VALUES ('login_token', '', ?1, '.cbc.ca', '/', ?2, 1, 1)",
+            rusqlite::params![encrypted, expires_utc],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO cookies VALUES ('lang', 'fr', x'', 'www.cbc.ca', '/', 0, 0, 0)",
+            [],
+        )
+        .unwrap();
+        drop(conn);
+
+        let session = session_from_source(&ImportSource::Chromium { profile: db }, NOW).unwrap();
+
+        assert_eq!(session.cookies.get("login_token"), Some(&"abc".to_string()));
+        assert_eq!(session.cookies.get("lang"), Some(&"fr".to_string()));
+        assert_eq!(session.expires_at, 1_800_000_000);
+    }
+
+    #[test]
+    fn test_decrypt_chromium_value_without_host_hash() {
+        let encrypted = encrypt_v10(b"abc");
+        assert_eq!(
+            decrypt_chromium_value(&encrypted, false),
+            Some("abc".to_string())
+        );
+        assert_eq!(decrypt_chromium_value(b"v11garbage", false), None);
+    }
+
+    #[test]
+    fn test_chromium_expiry() {
+        assert_eq!(chromium_expiry(0), None);
+        assert_eq!(
+            chromium_expiry((NOW + CHROMIUM_EPOCH_OFFSET) * 1_000_000),
+            Some(NOW)
+        );
+    }
+
+    #[test]
+    fn test_firefox_expiry_units() {
+        assert_eq!(firefox_expiry(0), None);
+        assert_eq!(firefox_expiry(NOW), Some(NOW));
+        assert_eq!(firefox_expiry(NOW * 1000), Some(NOW));
+    }
+}
@@ -0,0 +1,645 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::webview::Cookie;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{AuthSession, LoonieError};
+
+/// Cookies that carry the CBC login itself. When one of them expires the
+/// session is gone, whatever the other cookies say. The account profile is
+/// read from the first of them holding an identity token.
+pub const AUTH_COOKIE_NAMES: &[&str] = &["login_token", "cbc_login_token", "rc_access_token"];
+
+/// Domains whose cookies make up a CBC session. Subdomains are included.
+const CBC_DOMAINS: &[&str] = &["cbc.ca", "radio-canada.ca"];
+
+/// Used only when CBC issued nothing but browser-session cookies.
+const DEFAULT_SESSION_LIFETIME: i64 = 86400; // 24 hours
+
+/// A session cookie with the attributes that scope it. Cookies are told
+/// apart by name, domain and path, as in a browser.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CookieMeta {
+    pub name: String,
+    pub value: String,
+    /// The host that set the cookie when `host_only`, otherwise its `Domain`.
+    pub domain: String,
+    /// The cookie had no `Domain`, so it only goes back to that exact host.
+    #[serde(default)]
+    pub host_only: bool,
+    pub path: String,
+    /// Unix timestamp; `None` for browser-session cookies.
+    pub expires_at: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+}
+
+impl CookieMeta {
+    /// Reads a cookie received from `url`. A cookie without `Domain` is
+    /// host-only for that host, one without `Path` gets the URL's default
+    /// path, and one whose `Domain` doesn't cover the host is refused.
+    pub fn from_cookie(cookie: &Cookie<'_>, url: &Url, now: i64) -> Option<Self> {
+        let host = url.host_str()?;
+        let (domain, host_only) = match cookie.domain().filter(|d| !d.is_empty()) {
+            Some(domain) => (domain.trim_start_matches('.').to_ascii_lowercase(), false),
+            None => (host.to_string(), true),
+        };
+        if !domain_matches(&domain, host) {
+            return None;
+        }
+        let path = match cookie.path() {
+            Some(path) if path.starts_with('/') => path.to_string(),
+            _ => default_path(url),
+        };
+
+        Some(Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            host_only,
+            path,
+            expires_at: cookie_expiry(cookie, now),
+            secure: cookie.secure().unwrap_or(false),
+            http_only: cookie.http_only().unwrap_or(false),
+        })
+    }
+
+    fn same_key(&self, other: &CookieMeta) -> bool {
+        self.name == other.name && self.domain == other.domain && self.path == other.path
+    }
+
+    /// Whether a request to `url` should carry this cookie.
+    fn matches(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let domain_ok = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&self.domain, host)
+        };
+        domain_ok
+            && path_matches(&self.path, url.path())
+            && (url.scheme() == "https" || !self.secure)
+    }
+}
+
+/// Absolute expiry of a cookie. `Max-Age` wins over `Expires`, as in RFC 6265.
+fn cookie_expiry(cookie: &Cookie<'_>, now: i64) -> Option<i64> {
+    if let Some(max_age) = cookie.max_age() {
+        return Some(now + max_age.whole_seconds());
+    }
+    cookie.expires_datetime().map(|dt| dt.unix_timestamp())
+}
+
+/// Builds a session from the cookies the auth webview holds for `url`.
+pub fn session_from_cookies(cookies: &[Cookie<'_>], url: &Url, now: i64) -> Option<AuthSession> {
+    let cookies = cookies
+        .iter()
+        .filter_map(|c| CookieMeta::from_cookie(c, url, now))
+        .collect();
+    session_from_parts(cookies, now)
+}
+
+/// Builds a session from cookies however they were obtained. Empty and
+/// already expired cookies are skipped.
+pub fn session_from_parts(cookies: Vec<CookieMeta>, now: i64) -> Option<AuthSession> {
+    let cookie_meta: Vec<CookieMeta> = cookies
+        .into_iter()
+        .filter(|meta| !meta.name.is_empty() && !meta.value.is_empty())
+        .filter(|meta| meta.expires_at.map_or(true, |exp| exp > now))
+        .collect();
+
+    if cookie_meta.is_empty() {
+        return None;
+    }
+
+    let cookie_map: HashMap<String, String> = cookie_meta
+        .iter()
+        .map(|meta| (meta.name.clone(), meta.value.clone()))
+        .collect();
+
+    Some(AuthSession {
+        expires_at: session_expiry(&cookie_meta, now),
+        cookies: cookie_map,
+        cookie_meta,
+        user_id: None,
+        profile: None,
+    })
+}
+
+/// Applies the `Set-Cookie` headers of an authenticated response from `url`
+/// to the session. Returns true if any cookie was added, rotated, extended
+/// or removed.
+pub fn apply_set_cookies<'a>(
+    session: &mut AuthSession,
+    url: &Url,
+    set_cookies: impl IntoIterator<Item = &'a str>,
+    now: i64,
+) -> bool {
+    let mut changed = false;
+
+    for header in set_cookies {
+        let Some(meta) = Cookie::parse(header)
+            .ok()
+            .and_then(|cookie| CookieMeta::from_cookie(&cookie, url, now))
+        else {
+            continue;
+        };
+        let previous = session
+            .cookie_meta
+            .iter()
+            .position(|m| m.same_key(&meta))
+            .map(|index| session.cookie_meta.remove(index));
+
+        // An empty value or a past expiry is the server deleting the cookie
+        if meta.value.is_empty() || meta.expires_at.is_some_and(|exp| exp <= now) {
+            changed |= previous.is_some();
+            changed |= refresh_value(session, &meta.name);
+            continue;
+        }
+
+        // A sliding session re-issues the same value with a later expiry
+        changed |= previous.as_ref() != Some(&meta);
+        session
+            .cookies
+            .insert(meta.name.clone(), meta.value.clone());
+        session.cookie_meta.push(meta);
+    }
+
+    if changed {
+        session.expires_at = session_expiry(&session.cookie_meta, now);
+    }
+    changed
+}
+
+/// Points `session.cookies`, the values by name, at the cookie still called
+/// `name` after one was deleted, or drops the name. Returns true if the
+/// value was dropped.
+fn refresh_value(session: &mut AuthSession, name: &str) -> bool {
+    match session.cookie_meta.iter().rev().find(|m| m.name == name) {
+        Some(meta) => {
+            session.cookies.insert(name.to_string(), meta.value.clone());
+            false
+        }
+        None => session.cookies.remove(name).is_some(),
+    }
+}
+
+pub fn is_cbc_domain(domain: &str) -> bool {
+    let domain = domain.trim_start_matches('.');
+    CBC_DOMAINS.iter().any(|cbc| {
+        domain == *cbc
+            || domain
+                .strip_suffix(cbc)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
+/// Deletes the CBC cookies from the webview data store shared by the app's
+/// windows, so the next sign-in window doesn't reuse the old login. The
+/// main window is used to reach it, since an auth window may be closing.
+/// Returns how many were deleted.
+pub fn clear_webview_cookies<R: Runtime>(app: &AppHandle<R>) -> Result<usize, LoonieError> {
+    let Some(webview) = app.get_webview_window("main") else {
+        return Ok(0);
+    };
+
+    let cbc_cookies: Vec<Cookie<'static>> = webview
+        .cookies()
+        .map_err(|e| LoonieError::storage(format!("Failed to read webview cookies: {}", e)))?
+        .into_iter()
+        .filter(|c| c.domain().is_some_and(is_cbc_domain))
+        .collect();
+
+    let count = cbc_cookies.len();
+    for cookie in cbc_cookies {
+        webview
+            .delete_cookie(cookie)
+            .map_err(|e| LoonieError::storage(format!("Failed to delete webview cookie: {}", e)))?;
+    }
+    Ok(count)
+}
+
+/// RFC 6265 domain-match: `host` is `domain` or one of its subdomains.
+fn domain_matches(domain: &str, host: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// RFC 6265 path-match.
+fn path_matches(cookie_path: &str, request_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// RFC 6265 default path: the URL's path up to, not including, its last `/`.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(last) => url.path()[..last].to_string(),
+    }
+}
+
+/// `Cookie` header for a request to `url`, holding only the session cookies
+/// whose domain, path and `Secure` flag allow it.
+pub fn cookie_header_for(session: &AuthSession, url: &Url) -> Option<String> {
+    let mut pairs: Vec<(&str, &str)> = session
+        .cookie_meta
+        .iter()
+        .filter(|meta| meta.matches(url))
+        .map(|meta| (meta.name.as_str(), meta.value.as_str()))
+        .collect();
+
+    // Sessions saved before cookies were scoped only hold values by name.
+    // They were all captured from CBC, so they only go back there.
+    let to_cbc = url.scheme() == "https" && url.host_str().is_some_and(is_cbc_domain);
+    if to_cbc {
+        pairs.extend(
+            session
+                .cookies
+                .iter()
+                .filter(|(name, _)| session.cookie_meta.iter().all(|m| &m.name != *name))
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+    }
+
+    if pairs.is_empty() {
+        return None;
+    }
+    pairs.sort();
+
+    Some(
+        pairs
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("; "),
+    )
+}
+
+/// The session lives as long as its auth cookie. If none of the known auth
+/// cookies is present, the shortest-lived persistent http-only cookie is used.
+pub fn session_expiry(cookie_meta: &[CookieMeta], now: i64) -> i64 {
+    let essential = cookie_meta
+        .iter()
+        .find(|c| AUTH_COOKIE_NAMES.contains(&c.name.as_str()));
+
+    let expiry = match essential {
+        Some(cookie) => cookie.expires_at,
+        None => cookie_meta
+            .iter()
+            .filter(|c| c.http_only)
+            .filter_map(|c| c.expires_at)
+            .min(),
+    };
+
+    expiry.unwrap_or(now + DEFAULT_SESSION_LIFETIME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn parse(s: &str) -> Cookie<'static> {
+        Cookie::parse(s.to_string()).unwrap()
+    }
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    fn gem() -> Url {
+        url("https://gem.cbc.ca/live")
+    }
+
+    fn session(cookies: &[&str]) -> AuthSession {
+        let cookies: Vec<_> = cookies.iter().map(|c| parse(c)).collect();
+        session_from_cookies(&cookies, &gem(), NOW).unwrap()
+    }
+
+    #[test]
+    fn test_cookie_meta_captures_attributes() {
+        let cookie =
+            parse("login_token=abc; Domain=.cbc.ca; Path=/; Secure; HttpOnly; Max-Age=3600");
+        let meta = CookieMeta::from_cookie(&cookie, &gem(), NOW).unwrap();
+
+        assert_eq!(meta.name, "login_token");
+        assert_eq!(meta.value, "abc");
+        assert_eq!(meta.domain, "cbc.ca");
+        assert!(!meta.host_only);
+        assert_eq!(meta.path, "/");
+        assert_eq!(meta.expires_at, Some(NOW + 3600));
+        assert!(meta.secure);
+        assert!(meta.http_only);
+    }
+
+    #[test]
+    fn test_cookie_expiry_from_expires_attribute() {
+        let cookie = parse("a=b; Expires=Wed, 15 Nov 2023 22:13:20 GMT");
+        assert_eq!(cookie_expiry(&cookie, NOW), Some(1_700_086_400));
+    }
+
+    #[test]
+    fn test_cookie_expiry_max_age_wins_over_expires() {
+        let cookie = parse("a=b; Max-Age=60; Expires=Wed, 15 Nov 2023 22:13:20 GMT");
+        assert_eq!(cookie_expiry(&cookie, NOW), Some(NOW + 60));
+    }
+
+    #[test]
+    fn test_session_cookie_has_no_expiry() {
+        let cookie = parse("a=b; Path=/");
+        assert_eq!(cookie_expiry(&cookie, NOW), None);
+    }
+
+    #[test]
+    fn test_session_expiry_follows_auth_cookie() {
+        let cookies = [
+            parse("tracking=1; Max-Age=31536000; HttpOnly"),
+            parse("login_token=abc; Max-Age=7200; HttpOnly"),
+        ];
+        let session = session_from_cookies(&cookies, &gem(), NOW).unwrap();
+
+        assert_eq!(session.expires_at, NOW + 7200);
+        assert_eq!(session.cookie_meta.len(), 2);
+    }
+
+    #[test]
+    fn test_session_expiry_can_exceed_24_hours() {
+        let cookies = [parse("login_token=abc; Max-Age=1209600")];
+        let session = session_from_cookies(&cookies, &gem(), NOW).unwrap();
+
+        assert_eq!(session.expires_at, NOW + 1_209_600);
+    }
+
+    #[test]
+    fn test_session_expiry_falls_back_to_shortest_http_only_cookie() {
+        let cookies = [
+            parse("prefs=fr; Max-Age=60"),
+            parse("sid=abc; Max-Age=3600; HttpOnly"),
+            parse("rid=def; Max-Age=7200; HttpOnly"),
+        ];
+        let session = session_from_cookies(&cookies, &gem(), NOW).unwrap();
+
+        assert_eq!(session.expires_at, NOW + 3600);
+    }
+
+    #[test]
+    fn test_session_expiry_defaults_without_persistent_cookies() {
+        let cookies = [parse("sid=abc; HttpOnly")];
+        let session = session_from_cookies(&cookies, &gem(), NOW).unwrap();
+
+        assert_eq!(session.expires_at, NOW + DEFAULT_SESSION_LIFETIME);
+    }
+
+    #[test]
+    fn test_session_from_cookies_skips_empty_values() {
+        let cookies = [parse("empty="), parse("sid=abc")];
+        let session = session_from_cookies(&cookies, &gem(), NOW).unwrap();
+
+        assert_eq!(session.cookies.len(), 1);
+        assert_eq!(session.cookie_meta.len(), 1);
+        assert_eq!(session.cookies.get("sid"), Some(&"abc".to_string()));
+    }
+
+    #[test]
+    fn test_session_from_cookies_skips_expired() {
+        let cookies = [parse("old=1; Max-Age=0"), parse("sid=abc; Max-Age=60")];
+        let session = session_from_cookies(&cookies, &gem(), NOW).unwrap();
+
+        assert_eq!(session.cookies.len(), 1);
+        assert!(session.cookies.contains_key("sid"));
+    }
+
+    #[test]
+    fn test_apply_set_cookies_rotates_auth_cookie() {
+        let mut session = session(&["login_token=old; Max-Age=60"]);
+
+        let changed = apply_set_cookies(
+            &mut session,
+            &gem(),
+            ["login_token=new; Max-Age=7200; HttpOnly"],
+            NOW,
+        );
+
+        assert!(changed);
+        assert_eq!(session.cookies.get("login_token"), Some(&"new".to_string()));
+        assert_eq!(session.cookie_meta.len(), 1);
+        assert_eq!(session.expires_at, NOW + 7200);
+    }
+
+    #[test]
+    fn test_apply_set_cookies_unchanged_value() {
+        let mut session = session(&["sid=abc; HttpOnly"]);
+
+        assert!(!apply_set_cookies(
+            &mut session,
+            &gem(),
+            ["sid=abc; HttpOnly"],
+            NOW
+        ));
+    }
+
+    #[test]
+    fn test_apply_set_cookies_extends_reissued_cookie() {
+        let mut session = session(&["login_token=abc; Max-Age=60; HttpOnly"]);
+
+        let changed = apply_set_cookies(
+            &mut session,
+            &gem(),
+            ["login_token=abc; Max-Age=7200; HttpOnly"],
+            NOW + 30,
+        );
+
+        assert!(changed);
+        assert_eq!(session.expires_at, NOW + 30 + 7200);
+        assert_eq!(session.cookie_meta[0].expires_at, Some(NOW + 30 + 7200));
+    }
+
+    #[test]
+    fn test_apply_set_cookies_removes_deleted_cookie() {
+        let mut session = session(&["sid=abc", "other=1"]);
+
+        let changed = apply_set_cookies(&mut session, &gem(), ["sid=; Max-Age=0"], NOW);
+
+        assert!(changed);
+        assert!(!session.cookies.contains_key("sid"));
+        assert!(session.cookie_meta.iter().all(|m| m.name != "sid"));
+    }
+
+    #[test]
+    fn test_apply_set_cookies_ignores_malformed_headers() {
+        let mut session = session(&["sid=abc"]);
+
+        assert!(!apply_set_cookies(
+            &mut session,
+            &gem(),
+            ["no-equals-sign"],
+            NOW
+        ));
+        assert_eq!(session.cookies.len(), 1);
+    }
+
+    #[test]
+    fn test_is_cbc_domain() {
+        assert!(is_cbc_domain("cbc.ca"));
+        assert!(is_cbc_domain(".gem.cbc.ca"));
+        assert!(is_cbc_domain("services.radio-canada.ca"));
+        assert!(!is_cbc_domain("notcbc.ca"));
+        assert!(!is_cbc_domain("cbc.ca.example.com"));
+    }
+
+    #[test]
+    fn test_cookie_header_for_scopes_by_domain_and_path() {
+        let mut session = session(&["sid=abc; Domain=.cbc.ca; Path=/"]);
+        let services = url("https://services.radio-canada.ca/ott/");
+        apply_set_cookies(
+            &mut session,
+            &services,
+            [
+                "sid=rc; Domain=radio-canada.ca; Path=/",
+                "media=def; Domain=services.radio-canada.ca; Path=/media",
+            ],
+            NOW,
+        );
+
+        let header = |url: &str| cookie_header_for(&session, &url.parse().unwrap());
+        assert_eq!(
+            header("https://services.radio-canada.ca/media/validation/v2/"),
+            Some("media=def; sid=rc".to_string())
+        );
+        assert_eq!(
+            header("https://services.radio-canada.ca/ott/catalog"),
+            Some("sid=rc".to_string())
+        );
+        assert_eq!(
+            header("https://services.radio-canada.ca/mediafiles"),
+            Some("sid=rc".to_string())
+        );
+        assert_eq!(header("https://www.cbc.ca/"), Some("sid=abc".to_string()));
+        assert_eq!(header("https://notradio-canada.ca/"), None);
+    }
+
+    #[test]
+    fn test_host_only_cookie_stays_on_its_host() {
+        let session = session(&["gem=ghi"]);
+
+        assert!(session.cookie_meta[0].host_only);
+        assert_eq!(session.cookie_meta[0].domain, "gem.cbc.ca");
+        assert_eq!(
+            cookie_header_for(&session, &url("https://gem.cbc.ca/live")),
+            Some("gem=ghi".to_string())
+        );
+        assert_eq!(
+            cookie_header_for(&session, &url("https://sub.gem.cbc.ca/")),
+            None
+        );
+        assert_eq!(
+            cookie_header_for(&session, &url("https://akamaized.net/")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cookie_without_path_gets_default_path() {
+        let session = session_from_cookies(
+            &[parse("a=1")],
+            &url("https://gem.cbc.ca/media/validation/v2/"),
+            NOW,
+        )
+        .unwrap();
+
+        assert_eq!(session.cookie_meta[0].path, "/media/validation/v2");
+        assert_eq!(default_path(&url("https://gem.cbc.ca/live")), "/");
+    }
+
+    #[test]
+    fn test_apply_set_cookies_refuses_foreign_domain() {
+        let mut session = session(&["sid=abc"]);
+
+        let changed = apply_set_cookies(
+            &mut session,
+            &gem(),
+            [
+                "sid=evil; Domain=example.com",
+                "x=1; Domain=radio-canada.ca",
+            ],
+            NOW,
+        );
+
+        assert!(!changed);
+        assert_eq!(session.cookie_meta.len(), 1);
+        assert_eq!(session.cookies.get("sid"), Some(&"abc".to_string()));
+    }
+
+    #[test]
+    fn test_same_name_on_other_domain_is_a_separate_cookie() {
+        let mut session = session(&["sid=gem"]);
+        let services = url("https://services.radio-canada.ca/");
+
+        assert!(apply_set_cookies(&mut session, &services, ["sid=rc"], NOW));
+        assert_eq!(session.cookie_meta.len(), 2);
+        assert_eq!(
+            cookie_header_for(&session, &gem()),
+            Some("sid=gem".to_string())
+        );
+        assert_eq!(
+            cookie_header_for(&session, &services),
+            Some("sid=rc".to_string())
+        );
+
+        // Deleting one leaves the other, and its value, in place
+        assert!(apply_set_cookies(
+            &mut session,
+            &services,
+            ["sid=; Max-Age=0"],
+            NOW
+        ));
+        assert_eq!(session.cookie_meta.len(), 1);
+        assert_eq!(session.cookies.get("sid"), Some(&"gem".to_string()));
+    }
+
+    #[test]
+    fn test_cookie_header_for_keeps_secure_cookies_off_http() {
+        let session = session(&["sid=abc; Domain=cbc.ca; Secure", "lang=fr; Domain=cbc.ca"]);
+
+        assert_eq!(
+            cookie_header_for(&session, &url("http://www.cbc.ca/")),
+            Some("lang=fr".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cookie_header_for_sends_unscoped_cookies_only_to_cbc() {
+        let mut session = session(&["sid=abc; Domain=cbc.ca"]);
+        session
+            .cookies
+            .insert("legacy".to_string(), "1".to_string());
+
+        assert_eq!(
+            cookie_header_for(&session, &url("https://services.radio-canada.ca/")),
+            Some("legacy=1".to_string())
+        );
+        assert_eq!(
+            cookie_header_for(&session, &url("https://cbcgem.akamaized.net/")),
+            None
+        );
+        assert_eq!(
+            cookie_header_for(&session, &url("http://services.radio-canada.ca/")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_session_from_cookies_empty() {
+        assert!(session_from_cookies(&[], &gem(), NOW).is_none());
+        assert!(session_from_cookies(&[parse("empty=")], &gem(), NOW).is_none());
+    }
+}
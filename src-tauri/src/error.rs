@@ -0,0 +1,230 @@
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+
+use crate::ValidationFailure;
+
+/// Error returned by every command. It reaches the frontend as an object
+/// with a stable `code`, a human-readable `message` and the variant's
+/// fields, so callers can branch on the code instead of matching text.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LoonieError {
+    #[error("Not authenticated")]
+    NotAuthenticated,
+    #[error("Session expired - please login again")]
+    SessionExpired { account: Option<String> },
+    #[error("This stream is not available in your region")]
+    GeoBlocked { message: Option<String> },
+    #[error("Too many streams are playing on this account")]
+    ConcurrentStreamLimit { message: Option<String> },
+    #[error("This stream requires a premium subscription")]
+    PremiumRequired { message: Option<String> },
+    #[error("Stream is unavailable (error {error_code})")]
+    StreamUnavailable {
+        error_code: i32,
+        message: Option<String>,
+        reason: ValidationFailure,
+    },
+    #[error("{}", catalog_message(.status))]
+    CatalogUnavailable { status: Option<u16> },
+    #[error("Invalid stream URL format")]
+    InvalidStreamUrl { url: String },
+    #[error("Network error: {message}")]
+    Network {
+        message: String,
+        status: Option<u16>,
+    },
+    #[error("Failed to parse {what}: {message}")]
+    Parse { what: String, message: String },
+    #[error("Unknown account: {name}")]
+    UnknownAccount { name: String },
+    #[error("Unknown time zone: {name}")]
+    InvalidTimeZone { name: String },
+    #[error("Invalid catalog section: {slug:?}")]
+    InvalidCatalogSection { slug: String },
+    #[error("Only upcoming streams can have a reminder")]
+    ReminderNotUpcoming { stream_id: String },
+    #[error("Import failed: {message}")]
+    ImportFailed { message: String },
+    #[error("Storage error: {message}")]
+    Storage { message: String },
+    #[error("{message}")]
+    Internal { message: String },
+}
+
+fn catalog_message(status: &Option<u16>) -> String {
+    match status {
+        Some(status) => format!("Catalog API returned status: {}", status),
+        None => "Catalog is unavailable".to_string(),
+    }
+}
+
+impl LoonieError {
+    /// Stable identifier the frontend can match on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NotAuthenticated => "NOT_AUTHENTICATED",
+            Self::SessionExpired { .. } => "SESSION_EXPIRED",
+            Self::GeoBlocked { .. } => "GEO_BLOCKED",
+            Self::ConcurrentStreamLimit { .. } => "CONCURRENT_STREAM_LIMIT",
+            Self::PremiumRequired { .. } => "PREMIUM_REQUIRED",
+            Self::StreamUnavailable { .. } => "STREAM_UNAVAILABLE",
+            Self::CatalogUnavailable { .. } => "CATALOG_UNAVAILABLE",
+            Self::InvalidStreamUrl { .. } => "INVALID_STREAM_URL",
+            Self::Network { .. } => "NETWORK",
+            Self::Parse { .. } => "PARSE",
+            Self::UnknownAccount { .. } => "UNKNOWN_ACCOUNT",
+            Self::InvalidTimeZone { .. } => "INVALID_TIME_ZONE",
+            Self::InvalidCatalogSection { .. } => "INVALID_CATALOG_SECTION",
+            Self::ReminderNotUpcoming { .. } => "REMINDER_NOT_UPCOMING",
+            Self::ImportFailed { .. } => "IMPORT_FAILED",
+            Self::Storage { .. } => "STORAGE",
+            Self::Internal { .. } => "INTERNAL",
+        }
+    }
+
+    /// The validation failure behind this error, if the stream was refused
+    /// by the validation API.
+    pub fn validation_failure(&self) -> Option<ValidationFailure> {
+        match self {
+            Self::GeoBlocked { .. } => Some(ValidationFailure::GeoBlocked),
+            Self::ConcurrentStreamLimit { .. } => Some(ValidationFailure::ConcurrentStreamLimit),
+            Self::PremiumRequired { .. } => Some(ValidationFailure::NotEntitled),
+            Self::SessionExpired { .. } => Some(ValidationFailure::LoginRequired),
+            Self::StreamUnavailable { reason, .. } => Some(*reason),
+            _ => None,
+        }
+    }
+
+    pub fn parse(what: &str, error: impl std::fmt::Display) -> Self {
+        Self::Parse {
+            what: what.to_string(),
+            message: error.to_string(),
+        }
+    }
+
+    pub fn storage(error: impl std::fmt::Display) -> Self {
+        Self::Storage {
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<reqwest::Error> for LoonieError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Network {
+            status: error.status().map(|s| s.as_u16()),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl Serialize for LoonieError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+
+        match self {
+            Self::NotAuthenticated => {}
+            Self::SessionExpired { account } => map.serialize_entry("account", account)?,
+            Self::GeoBlocked { message }
+            | Self::ConcurrentStreamLimit { message }
+            | Self::PremiumRequired { message } => map.serialize_entry("detail", message)?,
+            Self::StreamUnavailable {
+                error_code,
+                message,
+                reason,
+            } => {
+                map.serialize_entry("error_code", error_code)?;
+                map.serialize_entry("detail", message)?;
+                map.serialize_entry("reason", reason)?;
+            }
+            Self::CatalogUnavailable { status } => map.serialize_entry("status", status)?,
+            Self::InvalidStreamUrl { url } => map.serialize_entry("url", url)?,
+            Self::Network { status, .. } => map.serialize_entry("status", status)?,
+            Self::Parse { what, .. } => map.serialize_entry("what", what)?,
+            Self::UnknownAccount { name } | Self::InvalidTimeZone { name } => {
+                map.serialize_entry("name", name)?
+            }
+            Self::InvalidCatalogSection { slug } => map.serialize_entry("slug", slug)?,
+            Self::ReminderNotUpcoming { stream_id } => {
+                map.serialize_entry("stream_id", stream_id)?
+            }
+            Self::ImportFailed { .. } | Self::Storage { .. } | Self::Internal { .. } => {}
+        }
+
+        if let Some(failure) = self.validation_failure() {
+            map.serialize_entry("explanation", &failure.explanation())?;
+            map.serialize_entry("hint", &failure.hint())?;
+        }
+
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unit_variant_serialization() {
+        let value = serde_json::to_value(LoonieError::NotAuthenticated).unwrap();
+        assert_eq!(
+            value,
+            json!({"code": "NOT_AUTHENTICATED", "message": "Not authenticated"})
+        );
+    }
+
+    #[test]
+    fn test_session_expired_keeps_legacy_message() {
+        let error = LoonieError::SessionExpired {
+            account: Some("Home".to_string()),
+        };
+        let value = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(value["code"], "SESSION_EXPIRED");
+        assert_eq!(value["message"], "Session expired - please login again");
+        assert_eq!(value["account"], "Home");
+    }
+
+    #[test]
+    fn test_structured_fields_are_serialized() {
+        let value = serde_json::to_value(LoonieError::StreamUnavailable {
+            error_code: 12,
+            message: Some("Not yet available".to_string()),
+            reason: ValidationFailure::NotYetAvailable,
+        })
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "code": "STREAM_UNAVAILABLE",
+                "message": "Stream is unavailable (error 12)",
+                "error_code": 12,
+                "detail": "Not yet available",
+                "reason": "not_yet_available",
+                "explanation": {
+                    "en": "This stream hasn't started yet.",
+                    "fr": "Cette diffusion n'a pas encore commencé."
+                },
+                "hint": {"retry": true, "relogin": false, "upgrade": false}
+            })
+        );
+
+        let value =
+            serde_json::to_value(LoonieError::CatalogUnavailable { status: Some(503) }).unwrap();
+        assert_eq!(value["status"], 503);
+        assert_eq!(value["message"], "Catalog API returned status: 503");
+    }
+
+    #[test]
+    fn test_parse_helper() {
+        let error = LoonieError::parse("catalog response", "expected value");
+        assert_eq!(error.code(), "PARSE");
+        assert_eq!(
+            error.to_string(),
+            "Failed to parse catalog response: expected value"
+        );
+    }
+}
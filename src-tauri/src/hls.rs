@@ -0,0 +1,336 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::LoonieError;
+
+/// What an HLS master playlist offers: the variant streams and the
+/// renditions they can be paired with. URIs are absolute.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct MasterPlaylist {
+    pub variants: Vec<Variant>,
+    pub audio: Vec<Rendition>,
+    pub subtitles: Vec<Rendition>,
+    pub closed_captions: Vec<Rendition>,
+    pub iframe_playlists: Vec<IFramePlaylist>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Variant {
+    pub uri: String,
+    /// Peak bits per second.
+    pub bandwidth: u64,
+    pub average_bandwidth: Option<u64>,
+    pub resolution: Option<Resolution>,
+    pub codecs: Vec<String>,
+    pub frame_rate: Option<f64>,
+    /// Group ids of the renditions this variant plays with.
+    pub audio_group: Option<String>,
+    pub subtitles_group: Option<String>,
+    pub closed_captions_group: Option<String>,
+}
+
+/// An `#EXT-X-MEDIA` entry. Closed captions are carried in the video and
+/// have an `instream_id` instead of a URI.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Rendition {
+    pub group_id: String,
+    pub name: String,
+    pub language: Option<String>,
+    pub uri: Option<String>,
+    pub instream_id: Option<String>,
+    pub channels: Option<String>,
+    pub is_default: bool,
+    pub autoselect: bool,
+    pub forced: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct IFramePlaylist {
+    pub uri: String,
+    pub bandwidth: u64,
+    pub resolution: Option<Resolution>,
+    pub codecs: Vec<String>,
+}
+
+/// Splits an attribute list (`KEY=value,KEY="quoted, value"`) into pairs,
+/// with quotes removed.
+fn parse_attributes(list: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    let mut rest = list.trim();
+
+    while !rest.is_empty() {
+        let Some((key, after)) = rest.split_once('=') else {
+            break;
+        };
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, after)) => (value, after),
+                None => (quoted, ""),
+            },
+            None => after.split_once(',').map_or((after, ""), |(v, a)| (v, a)),
+        };
+        attributes.push((key.trim(), value));
+        rest = after.trim_start_matches(',').trim_start();
+    }
+
+    attributes
+}
+
+pub(crate) struct Attributes<'a>(Vec<(&'a str, &'a str)>);
+
+impl<'a> Attributes<'a> {
+    pub(crate) fn parse(list: &'a str) -> Self {
+        Self(parse_attributes(list))
+    }
+
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.0.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    }
+
+    fn string(&self, key: &str) -> Option<String> {
+        self.get(key).map(str::to_string)
+    }
+
+    fn flag(&self, key: &str) -> bool {
+        self.get(key) == Some("YES")
+    }
+
+    pub(crate) fn number<T: std::str::FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|v| v.parse().ok())
+    }
+
+    pub(crate) fn resolution(&self) -> Option<Resolution> {
+        let (width, height) = self.get("RESOLUTION")?.split_once('x')?;
+        Some(Resolution {
+            width: width.parse().ok()?,
+            height: height.parse().ok()?,
+        })
+    }
+
+    fn codecs(&self) -> Vec<String> {
+        self.get("CODECS")
+            .map(|codecs| codecs.split(',').map(|c| c.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+}
+
+fn resolve(base: &Url, uri: &str) -> String {
+    base.join(uri)
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| uri.to_string())
+}
+
+/// Parses the master playlist at `base`. Unknown tags are skipped.
+pub fn parse_master(body: &str, base: &Url) -> Result<MasterPlaylist, LoonieError> {
+    let mut lines = body.lines().map(str::trim).filter(|l| !l.is_empty());
+    if lines.next() != Some("#EXTM3U") {
+        return Err(LoonieError::parse("HLS playlist", "missing #EXTM3U header"));
+    }
+
+    let mut playlist = MasterPlaylist::default();
+    let mut pending: Option<Attributes> = None;
+
+    for line in lines {
+        if let Some(list) = line.strip_prefix("#EXT-X-STREAM-INF:") {
+            pending = Some(Attributes::parse(list));
+        } else if let Some(list) = line.strip_prefix("#EXT-X-MEDIA:") {
+            let attributes = Attributes::parse(list);
+            let rendition = Rendition {
+                group_id: attributes.string("GROUP-ID").unwrap_or_default(),
+                name: attributes.string("NAME").unwrap_or_default(),
+                language: attributes.string("LANGUAGE"),
+                uri: attributes.get("URI").map(|uri| resolve(base, uri)),
+                instream_id: attributes.string("INSTREAM-ID"),
+                channels: attributes.string("CHANNELS"),
+                is_default: attributes.flag("DEFAULT"),
+                autoselect: attributes.flag("AUTOSELECT"),
+                forced: attributes.flag("FORCED"),
+            };
+            match attributes.get("TYPE") {
+                Some("AUDIO") => playlist.audio.push(rendition),
+                Some("SUBTITLES") => playlist.subtitles.push(rendition),
+                Some("CLOSED-CAPTIONS") => playlist.closed_captions.push(rendition),
+                _ => {}
+            }
+        } else if let Some(list) = line.strip_prefix("#EXT-X-I-FRAME-STREAM-INF:") {
+            let attributes = Attributes::parse(list);
+            if let (Some(uri), Some(bandwidth)) =
+                (attributes.get("URI"), attributes.number("BANDWIDTH"))
+            {
+                playlist.iframe_playlists.push(IFramePlaylist {
+                    uri: resolve(base, uri),
+                    bandwidth,
+                    resolution: attributes.resolution(),
+                    codecs: attributes.codecs(),
+                });
+            }
+        } else if line.starts_with("#EXTINF:") {
+            return Err(LoonieError::parse(
+                "HLS playlist",
+                "expected a master playlist, got a media playlist",
+            ));
+        } else if !line.starts_with('#') {
+            // The URI line after an #EXT-X-STREAM-INF tag
+            let Some(attributes) = pending.take() else {
+                continue;
+            };
+            let Some(bandwidth) = attributes.number("BANDWIDTH") else {
+                continue;
+            };
+            playlist.variants.push(Variant {
+                uri: resolve(base, line),
+                bandwidth,
+                average_bandwidth: attributes.number("AVERAGE-BANDWIDTH"),
+                resolution: attributes.resolution(),
+                codecs: attributes.codecs(),
+                frame_rate: attributes.number("FRAME-RATE"),
+                audio_group: attributes.string("AUDIO"),
+                subtitles_group: attributes.string("SUBTITLES"),
+                closed_captions_group: attributes
+                    .get("CLOSED-CAPTIONS")
+                    .filter(|group| *group != "NONE")
+                    .map(str::to_string),
+            });
+        }
+    }
+
+    Ok(playlist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str =
+        "https://cbcrclive.akamaized.net/hls/live/2036000/olympics/master.m3u8?hdnea=st";
+
+    const MASTER: &str = r#"#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-INDEPENDENT-SEGMENTS
+
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",LANGUAGE="fr",NAME="Français",DEFAULT=YES,AUTOSELECT=YES,CHANNELS="2",URI="audio/fr.m3u8"
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID="aac",LANGUAGE="en",NAME="English",DEFAULT=NO,AUTOSELECT=YES,CHANNELS="2",URI="audio/en.m3u8"
+#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID="subs",LANGUAGE="fr",NAME="Français (SME)",FORCED=NO,URI="subs/fr.m3u8"
+#EXT-X-MEDIA:TYPE=CLOSED-CAPTIONS,GROUP-ID="cc",LANGUAGE="en",NAME="English CC",INSTREAM-ID="CC1"
+#EXT-X-STREAM-INF:BANDWIDTH=6000000,AVERAGE-BANDWIDTH=5500000,RESOLUTION=1920x1080,FRAME-RATE=59.940,CODECS="avc1.640028,mp4a.40.2",AUDIO="aac",SUBTITLES="subs",CLOSED-CAPTIONS="cc"
+1080p/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360,CODECS="avc1.4d401e,mp4a.40.2",AUDIO="aac",CLOSED-CAPTIONS=NONE
+https://cdn.example.com/360p/index.m3u8
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=200000,RESOLUTION=1920x1080,CODECS="avc1.640028",URI="1080p/iframes.m3u8"
+"#;
+
+    fn parse(body: &str) -> Result<MasterPlaylist, LoonieError> {
+        parse_master(body, &Url::parse(BASE).unwrap())
+    }
+
+    #[test]
+    fn test_attributes_keep_commas_inside_quotes() {
+        assert_eq!(
+            parse_attributes(r#"BANDWIDTH=1,CODECS="avc1.640028,mp4a.40.2",NAME="A, B""#),
+            vec![
+                ("BANDWIDTH", "1"),
+                ("CODECS", "avc1.640028,mp4a.40.2"),
+                ("NAME", "A, B"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variants() {
+        let playlist = parse(MASTER).unwrap();
+
+        assert_eq!(
+            playlist.variants[0],
+            Variant {
+                uri: "https://cbcrclive.akamaized.net/hls/live/2036000/olympics/1080p/index.m3u8"
+                    .to_string(),
+                bandwidth: 6_000_000,
+                average_bandwidth: Some(5_500_000),
+                resolution: Some(Resolution {
+                    width: 1920,
+                    height: 1080
+                }),
+                codecs: vec!["avc1.640028".to_string(), "mp4a.40.2".to_string()],
+                frame_rate: Some(59.94),
+                audio_group: Some("aac".to_string()),
+                subtitles_group: Some("subs".to_string()),
+                closed_captions_group: Some("cc".to_string()),
+            }
+        );
+        assert_eq!(
+            playlist.variants[1].uri,
+            "https://cdn.example.com/360p/index.m3u8"
+        );
+        assert_eq!(playlist.variants[1].frame_rate, None);
+        assert_eq!(playlist.variants[1].closed_captions_group, None);
+    }
+
+    #[test]
+    fn test_renditions() {
+        let playlist = parse(MASTER).unwrap();
+
+        let audio: Vec<_> = playlist
+            .audio
+            .iter()
+            .map(|r| (r.language.as_deref(), r.name.as_str(), r.is_default))
+            .collect();
+        assert_eq!(
+            audio,
+            vec![
+                (Some("fr"), "Français", true),
+                (Some("en"), "English", false)
+            ]
+        );
+        assert_eq!(playlist.audio[0].channels.as_deref(), Some("2"));
+        assert!(playlist.audio[0]
+            .uri
+            .as_deref()
+            .unwrap()
+            .ends_with("/olympics/audio/fr.m3u8"));
+
+        assert_eq!(playlist.subtitles.len(), 1);
+        assert!(!playlist.subtitles[0].forced);
+
+        assert_eq!(
+            playlist.closed_captions[0].instream_id.as_deref(),
+            Some("CC1")
+        );
+        assert_eq!(playlist.closed_captions[0].uri, None);
+    }
+
+    #[test]
+    fn test_iframe_playlists() {
+        let playlist = parse(MASTER).unwrap();
+
+        assert_eq!(
+            playlist.iframe_playlists,
+            vec![IFramePlaylist {
+                uri: "https://cbcrclive.akamaized.net/hls/live/2036000/olympics/1080p/iframes.m3u8"
+                    .to_string(),
+                bandwidth: 200_000,
+                resolution: Some(Resolution {
+                    width: 1920,
+                    height: 1080
+                }),
+                codecs: vec!["avc1.640028".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rejects_what_is_not_a_master_playlist() {
+        assert!(matches!(
+            parse("<html></html>"),
+            Err(LoonieError::Parse { .. })
+        ));
+
+        let media = "#EXTM3U\n#EXT-X-TARGETDURATION:6\n#EXTINF:6.0,\nsegment1.ts\n";
+        assert!(matches!(parse(media), Err(LoonieError::Parse { .. })));
+    }
+}
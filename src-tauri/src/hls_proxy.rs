@@ -0,0 +1,954 @@
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use futures_util::StreamExt;
+use hyper::server::conn::AddrIncoming;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::{
+    HeaderMap, ACCEPT, ACCEPT_RANGES, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_EXPOSE_HEADERS, CACHE_CONTROL, CONTENT_RANGE, CONTENT_TYPE, COOKIE, RANGE,
+    SET_COOKIE,
+};
+use reqwest::Url;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::hls::{self, MasterPlaylist};
+use crate::quality::{self, QualityCap, QualityState};
+use crate::{HttpClient, LoonieError};
+
+/// Path of a route's master playlist; everything else is addressed by URL.
+const MASTER_PATH: &str = "master.m3u8";
+
+const PLAYLIST_CONTENT_TYPE: &str = "application/vnd.apple.mpegurl";
+
+/// Segments are worth retrying before the player gives up on them.
+/// Playlists aren't: the player reloads them on its own.
+const SEGMENT_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// A viewport counts as playing, for splitting the bandwidth budget, while
+/// its player has made a request this recently.
+const PLAYING_WINDOW: Duration = Duration::from_secs(30);
+
+/// Response headers passed through from the origin.
+const FORWARDED_HEADERS: [reqwest::header::HeaderName; 4] =
+    [CONTENT_TYPE, CONTENT_RANGE, ACCEPT_RANGES, CACHE_CONTROL];
+
+pub type UpstreamFuture<'a> =
+    Pin<Box<dyn Future<Output = reqwest::Result<reqwest::Response>> + Send + 'a>>;
+
+/// Where the proxy fetches from. Requests go out without the account's
+/// cookies: streams are served from the CDN, which doesn't need them.
+pub trait Upstream: Send + Sync + 'static {
+    fn fetch(&self, method: Method, url: Url, headers: HeaderMap) -> UpstreamFuture<'_>;
+}
+
+impl Upstream for AppHandle {
+    fn fetch(&self, method: Method, url: Url, headers: HeaderMap) -> UpstreamFuture<'_> {
+        Box::pin(async move {
+            let http = self.state::<HttpClient>();
+            http.client()
+                .request(method, url)
+                .headers(headers)
+                .send()
+                .await
+        })
+    }
+}
+
+/// Bytes served to one viewport's player since the app started.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct ViewportTraffic {
+    pub viewport_index: u32,
+    pub bytes: u64,
+    pub requests: u64,
+}
+
+/// Traffic by viewport, shared with the segment bodies still streaming.
+#[derive(Clone, Default)]
+struct Traffic(Arc<Mutex<BTreeMap<u32, ViewportTraffic>>>);
+
+impl Traffic {
+    fn add(&self, viewport_index: Option<u32>, bytes: usize, requests: u64) {
+        let Some(viewport_index) = viewport_index else {
+            return;
+        };
+        let mut traffic = self.0.lock().unwrap();
+        let entry = traffic.entry(viewport_index).or_insert(ViewportTraffic {
+            viewport_index,
+            ..Default::default()
+        });
+        entry.bytes += bytes as u64;
+        entry.requests += requests;
+    }
+
+    fn list(&self) -> Vec<ViewportTraffic> {
+        self.0.lock().unwrap().values().cloned().collect()
+    }
+}
+
+/// A stream opened through the proxy.
+#[derive(Debug, Clone)]
+struct Route {
+    viewport_index: Option<u32>,
+    master_url: Url,
+    /// Origins the route's playlists have pointed at. A proxied URL on any
+    /// other origin is refused.
+    origins: Arc<Mutex<HashSet<String>>>,
+    /// Cookies the CDN set for this stream, such as Akamai tokens. They
+    /// never reach the account's session.
+    cookies: Arc<Jar>,
+    /// The master playlist as the CDN last served it, before filtering,
+    /// and the limit the player got it filtered to.
+    master: Arc<Mutex<Option<(MasterPlaylist, QualityCap)>>>,
+}
+
+impl Route {
+    fn allow(&self, url: &Url) {
+        self.origins
+            .lock()
+            .unwrap()
+            .insert(url.origin().ascii_serialization());
+    }
+
+    fn allows(&self, url: &Url) -> bool {
+        self.origins
+            .lock()
+            .unwrap()
+            .contains(&url.origin().ascii_serialization())
+    }
+
+    /// Whether the player may load `url` under the limit its master
+    /// playlist was filtered to. Only that playlist's variants are ever
+    /// refused.
+    fn permits(&self, url: &Url) -> bool {
+        match self.master.lock().unwrap().as_ref() {
+            Some((master, limit)) => limit.permits(master, url.as_str()),
+            None => true,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Routes {
+    by_token: HashMap<String, Route>,
+    /// One route per viewport; opening another stream replaces it. Routes
+    /// without a viewport stay until they're closed.
+    by_viewport: HashMap<u32, String>,
+}
+
+struct Inner {
+    addr: SocketAddr,
+    upstream: Box<dyn Upstream>,
+    routes: Mutex<Routes>,
+    traffic: Traffic,
+    last_request: Mutex<HashMap<u32, Instant>>,
+    quality: QualityState,
+}
+
+/// Localhost server the players load their streams from. It fetches
+/// playlists and segments from the CDN and points every URI in the
+/// playlists back at itself.
+///
+/// Routes are addressed by a random token, and each only fetches from the
+/// origins its own playlists named, so other local processes can't use the
+/// proxy to reach anything else.
+#[derive(Clone)]
+pub struct HlsProxy {
+    inner: Arc<Inner>,
+}
+
+impl HlsProxy {
+    /// Binds to a free port on 127.0.0.1 and starts serving. Master
+    /// playlists are filtered to the limits in `quality`.
+    pub async fn start(
+        upstream: impl Upstream,
+        quality: QualityState,
+    ) -> Result<Self, LoonieError> {
+        let incoming =
+            AddrIncoming::bind(&SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).map_err(|e| {
+                LoonieError::Internal {
+                    message: format!("Failed to start the stream proxy: {}", e),
+                }
+            })?;
+
+        let inner = Arc::new(Inner {
+            addr: incoming.local_addr(),
+            upstream: Box::new(upstream),
+            routes: Mutex::new(Routes::default()),
+            traffic: Traffic::default(),
+            last_request: Mutex::new(HashMap::new()),
+            quality,
+        });
+
+        let service_inner = inner.clone();
+        let make_service = make_service_fn(move |_| {
+            let inner = service_inner.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let inner = inner.clone();
+                    async move { Ok::<_, Infallible>(inner.handle(request).await) }
+                }))
+            }
+        });
+        let server = Server::builder(incoming).serve(make_service);
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                eprintln!("Stream proxy stopped: {}", e);
+            }
+        });
+
+        Ok(Self { inner })
+    }
+
+    /// Routes `master_url` for a viewport and returns the URL its player
+    /// should load instead.
+    pub fn open(&self, viewport_index: Option<u32>, master_url: Url) -> String {
+        let token = new_token();
+        let route = Route {
+            viewport_index,
+            master_url,
+            origins: Arc::default(),
+            cookies: Arc::default(),
+            master: Arc::default(),
+        };
+        route.allow(&route.master_url);
+
+        let mut routes = self.inner.routes.lock().unwrap();
+        if let Some(viewport_index) = viewport_index {
+            if let Some(old) = routes.by_viewport.insert(viewport_index, token.clone()) {
+                routes.by_token.remove(&old);
+            }
+        }
+        routes.by_token.insert(token.clone(), route);
+        format!("http://{}/{}/{}", self.inner.addr, token, MASTER_PATH)
+    }
+
+    /// Stops routing the stream `open` returned `url` for. Anything else,
+    /// like a stream that wasn't proxied, is ignored.
+    pub fn close(&self, url: &str) {
+        let token = Url::parse(url)
+            .ok()
+            .and_then(|url| Some(url.path_segments()?.next()?.to_string()));
+        let Some(token) = token else {
+            return;
+        };
+
+        let mut routes = self.inner.routes.lock().unwrap();
+        let Some(route) = routes.by_token.remove(&token) else {
+            return;
+        };
+        if let Some(viewport_index) = route.viewport_index {
+            if routes.by_viewport.get(&viewport_index) == Some(&token) {
+                routes.by_viewport.remove(&viewport_index);
+            }
+        }
+    }
+
+    pub fn traffic(&self) -> Vec<ViewportTraffic> {
+        self.inner.traffic.list()
+    }
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl Inner {
+    async fn handle(&self, request: Request<Body>) -> Response<Body> {
+        let method = request.method().clone();
+        match method {
+            Method::GET | Method::HEAD => {}
+            Method::OPTIONS => return respond(StatusCode::NO_CONTENT),
+            _ => return respond(StatusCode::METHOD_NOT_ALLOWED),
+        }
+
+        let path = request.uri().path().trim_start_matches('/');
+        let (token, rest) = path.split_once('/').unwrap_or((path, ""));
+        let Some(route) = self.routes.lock().unwrap().by_token.get(token).cloned() else {
+            return respond(StatusCode::NOT_FOUND);
+        };
+        if let Some(viewport_index) = route.viewport_index {
+            self.last_request
+                .lock()
+                .unwrap()
+                .insert(viewport_index, Instant::now());
+        }
+
+        let url = if rest == MASTER_PATH {
+            route.master_url.clone()
+        } else {
+            match upstream_url(request.uri().query()) {
+                Some(url) if route.allows(&url) => url,
+                Some(_) => return respond(StatusCode::FORBIDDEN),
+                None => return respond(StatusCode::BAD_REQUEST),
+            }
+        };
+        // The player could still ask for a variant its master playlist
+        // left out
+        if !route.permits(&url) {
+            return respond(StatusCode::FORBIDDEN);
+        }
+
+        let mut headers = HeaderMap::new();
+        for name in [ACCEPT, RANGE] {
+            if let Some(value) = request.headers().get(&name) {
+                headers.insert(name, value.clone());
+            }
+        }
+
+        let attempts = if is_playlist_url(&url) {
+            1
+        } else {
+            SEGMENT_ATTEMPTS
+        };
+        let Some(upstream) = self
+            .fetch(&route, method.clone(), &url, headers, attempts)
+            .await
+        else {
+            return respond(StatusCode::BAD_GATEWAY);
+        };
+
+        // Where a redirect left off, which relative URIs resolve against
+        let base = upstream.url().clone();
+        let status = upstream.status();
+        let is_playlist =
+            status.is_success() && (is_playlist_url(&url) || is_playlist_type(upstream.headers()));
+        let mut response = Response::builder().status(status);
+        if is_playlist {
+            // Rewritten, so the origin's length and range no longer apply
+            response = response
+                .header(CONTENT_TYPE, PLAYLIST_CONTENT_TYPE)
+                .header(CACHE_CONTROL, "no-cache");
+        } else {
+            for name in FORWARDED_HEADERS {
+                if let Some(value) = upstream.headers().get(&name) {
+                    response = response.header(name, value.clone());
+                }
+            }
+        }
+
+        let body = if method == Method::HEAD {
+            Body::empty()
+        } else if is_playlist {
+            let body = match upstream.bytes().await {
+                Ok(body) => body,
+                Err(e) => {
+                    eprintln!("Stream proxy failed to read {}: {}", url, e);
+                    return respond(StatusCode::BAD_GATEWAY);
+                }
+            };
+            let mut playlist = String::from_utf8_lossy(&body).into_owned();
+            route.allow(&base);
+            if rest == MASTER_PATH {
+                // Kept with the playlist, so variant requests are held to the
+                // limit this player was given, however the budget has been
+                // split since. A new limit applies when the player reloads it.
+                let limit = route
+                    .viewport_index
+                    .map_or_else(QualityCap::default, |index| {
+                        self.quality.limit_for(index, &self.playing())
+                    });
+                let master = hls::parse_master(&playlist, &base).ok();
+                *route.master.lock().unwrap() = master.map(|master| (master, limit));
+                playlist = quality::filter_master(&playlist, &limit);
+            }
+            let playlist = rewrite_playlist(&playlist, &base, |uri| {
+                route.allow(uri);
+                self.proxy_url(token, uri)
+            });
+            self.traffic.add(route.viewport_index, playlist.len(), 1);
+            Body::from(playlist)
+        } else {
+            // Segments go out as they arrive, counted chunk by chunk
+            self.traffic.add(route.viewport_index, 0, 1);
+            let traffic = self.traffic.clone();
+            let viewport_index = route.viewport_index;
+            Body::wrap_stream(upstream.bytes_stream().inspect(move |chunk| match chunk {
+                Ok(chunk) => traffic.add(viewport_index, chunk.len(), 0),
+                Err(e) => eprintln!("Stream proxy failed to read {}: {}", url, e),
+            }))
+        };
+        with_cors(response)
+            .body(body)
+            .unwrap_or_else(|_| respond(StatusCode::INTERNAL_SERVER_ERROR))
+    }
+
+    /// Fetches `url` with the route's CDN cookies, retrying network errors
+    /// and server errors.
+    async fn fetch(
+        &self,
+        route: &Route,
+        method: Method,
+        url: &Url,
+        mut headers: HeaderMap,
+        attempts: u32,
+    ) -> Option<reqwest::Response> {
+        if let Some(cookies) = route.cookies.cookies(url) {
+            headers.insert(COOKIE, cookies);
+        }
+
+        for attempt in 1..=attempts {
+            let result = self
+                .upstream
+                .fetch(method.clone(), url.clone(), headers.clone())
+                .await;
+            match result {
+                Ok(response) if !response.status().is_server_error() || attempt == attempts => {
+                    let mut set_cookies = response.headers().get_all(SET_COOKIE).iter();
+                    route.cookies.set_cookies(&mut set_cookies, response.url());
+                    return Some(response);
+                }
+                Ok(response) => {
+                    eprintln!(
+                        "Stream proxy got {} for {}, retrying",
+                        response.status(),
+                        url
+                    )
+                }
+                Err(e) => eprintln!("Stream proxy failed to fetch {}: {}", url, e),
+            }
+            if attempt < attempts {
+                tokio::time::sleep(RETRY_DELAY * attempt).await;
+            }
+        }
+        None
+    }
+
+    fn proxy_url(&self, token: &str, upstream: &Url) -> String {
+        // Keeping the file name makes the proxied URLs readable in logs
+        let file = upstream
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|file| !file.is_empty())
+            .unwrap_or("resource");
+        let mut url = Url::parse(&format!("http://{}", self.addr)).expect("valid proxy address");
+        url.path_segments_mut()
+            .expect("proxy URL has a path")
+            .push(token)
+            .push(file);
+        url.query_pairs_mut().append_pair("u", upstream.as_str());
+        url.to_string()
+    }
+
+    fn playing(&self) -> Vec<u32> {
+        let now = Instant::now();
+        self.last_request
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, at)| now.duration_since(**at) < PLAYING_WINDOW)
+            .map(|(viewport_index, _)| *viewport_index)
+            .collect()
+    }
+}
+
+fn with_cors(response: hyper::http::response::Builder) -> hyper::http::response::Builder {
+    response
+        .header(ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(ACCESS_CONTROL_ALLOW_HEADERS, "Range")
+        .header(
+            ACCESS_CONTROL_EXPOSE_HEADERS,
+            "Content-Range, Content-Length",
+        )
+}
+
+fn respond(status: StatusCode) -> Response<Body> {
+    with_cors(Response::builder().status(status))
+        .body(Body::empty())
+        .expect("empty response")
+}
+
+/// The origin URL in a proxied request's `u` parameter.
+fn upstream_url(query: Option<&str>) -> Option<Url> {
+    let query = query?;
+    let url = Url::parse(&format!("http://proxy/?{}", query)).ok()?;
+    let (_, value) = url.query_pairs().find(|(key, _)| key == "u")?;
+    Url::parse(&value)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+fn is_playlist_url(url: &Url) -> bool {
+    url.path().ends_with(".m3u8")
+}
+
+fn is_playlist_type(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_ascii_lowercase().contains("mpegurl"))
+        .unwrap_or(false)
+}
+
+/// Replaces the quoted value of the first `URI` attribute in a tag.
+fn rewrite_uri_attribute(line: &str, rewrite: impl Fn(&str) -> Option<String>) -> String {
+    let found = line
+        .match_indices("URI=\"")
+        .find(|(index, _)| matches!(line[..*index].chars().next_back(), Some(':') | Some(',')));
+    let Some((index, pattern)) = found else {
+        return line.to_string();
+    };
+
+    let start = index + pattern.len();
+    let Some(length) = line[start..].find('"') else {
+        return line.to_string();
+    };
+    match rewrite(&line[start..start + length]) {
+        Some(uri) => format!("{}{}{}", &line[..start], uri, &line[start + length..]),
+        None => line.to_string(),
+    }
+}
+
+/// Points every http(s) URI in `playlist`, fetched from `base`, at the
+/// proxy. Other URIs, like `skd://` keys, are left for the player.
+pub fn rewrite_playlist(playlist: &str, base: &Url, proxy_url: impl Fn(&Url) -> String) -> String {
+    let rewrite = |uri: &str| {
+        base.join(uri)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .map(|url| proxy_url(&url))
+    };
+
+    let mut out = String::with_capacity(playlist.len());
+    for line in playlist.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            out.push_str(line);
+        } else if trimmed.starts_with('#') {
+            out.push_str(&rewrite_uri_attribute(line, rewrite));
+        } else {
+            out.push_str(&rewrite(trimmed).unwrap_or_else(|| line.to_string()));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quality::{QualityCap, QualitySettings};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const MASTER: &str = "#EXTM3U
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",URI=\"audio/en.m3u8\"
+#EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360,AUDIO=\"aac\"
+360p/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=6000000,RESOLUTION=1920x1080,AUDIO=\"aac\"
+1080p/index.m3u8
+";
+
+    const MEDIA: &str = "#EXTM3U
+#EXT-X-TARGETDURATION:6
+#EXT-X-MAP:URI=\"init.mp4\"
+#EXTINF:6.0,
+segment1.ts
+";
+
+    const CDN_COOKIE: &str = "hdntl=exp~token";
+
+    /// Fetches directly, remembering the method and cookies of each request.
+    struct Direct {
+        client: reqwest::Client,
+        requests: Mutex<Vec<(Method, Option<String>)>>,
+    }
+
+    impl Upstream for Arc<Direct> {
+        fn fetch(&self, method: Method, url: Url, headers: HeaderMap) -> UpstreamFuture<'_> {
+            let cookie = headers
+                .get(COOKIE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            self.requests.lock().unwrap().push((method.clone(), cookie));
+            Box::pin(self.client.request(method, url).headers(headers).send())
+        }
+    }
+
+    /// A CDN serving one stream whose segment fails `failures` times. The
+    /// master playlist sets a token cookie, like Akamai does.
+    async fn fake_origin(failures: usize) -> SocketAddr {
+        let remaining = Arc::new(AtomicUsize::new(failures));
+        let make_service = make_service_fn(move |_| {
+            let remaining = remaining.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let remaining = remaining.clone();
+                    async move {
+                        let (status, body) = match request.uri().path() {
+                            "/live/master.m3u8" => (StatusCode::OK, MASTER),
+                            "/live/360p/index.m3u8" => (StatusCode::OK, MEDIA),
+                            "/live/360p/segment1.ts" => {
+                                let failed = remaining
+                                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                                        n.checked_sub(1)
+                                    })
+                                    .is_ok();
+                                if failed {
+                                    (StatusCode::SERVICE_UNAVAILABLE, "")
+                                } else {
+                                    (StatusCode::OK, "segment-bytes")
+                                }
+                            }
+                            _ => (StatusCode::NOT_FOUND, ""),
+                        };
+                        let mut response = Response::builder().status(status);
+                        if request.uri().path() == "/live/master.m3u8" {
+                            response =
+                                response.header(SET_COOKIE, format!("{}; Path=/live", CDN_COOKIE));
+                        }
+                        Ok::<_, Infallible>(response.body(Body::from(body)).unwrap())
+                    }
+                }))
+            }
+        });
+
+        let incoming = AddrIncoming::bind(&SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).unwrap();
+        let addr = incoming.local_addr();
+        tokio::spawn(Server::builder(incoming).serve(make_service));
+        addr
+    }
+
+    /// A server that redirects every request to `target`, like a CDN
+    /// handing a stream off to one of its edges.
+    async fn redirect_to(target: Url) -> SocketAddr {
+        let make_service = make_service_fn(move |_| {
+            let target = target.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_: Request<Body>| {
+                    let response = Response::builder()
+                        .status(StatusCode::FOUND)
+                        .header(reqwest::header::LOCATION, target.as_str())
+                        .body(Body::empty())
+                        .unwrap();
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+
+        let incoming = AddrIncoming::bind(&SocketAddr::from((Ipv4Addr::LOCALHOST, 0))).unwrap();
+        let addr = incoming.local_addr();
+        tokio::spawn(Server::builder(incoming).serve(make_service));
+        addr
+    }
+
+    async fn start_proxy_with(quality: QualityState) -> (HlsProxy, Arc<Direct>) {
+        let direct = Arc::new(Direct {
+            client: reqwest::Client::new(),
+            requests: Mutex::new(Vec::new()),
+        });
+        (
+            HlsProxy::start(direct.clone(), quality).await.unwrap(),
+            direct,
+        )
+    }
+
+    async fn start_proxy() -> (HlsProxy, Arc<Direct>) {
+        start_proxy_with(QualityState::default()).await
+    }
+
+    fn master_url(origin: SocketAddr) -> Url {
+        Url::parse(&format!("http://{}/live/master.m3u8", origin)).unwrap()
+    }
+
+    async fn get(url: &str) -> (StatusCode, String) {
+        let response = reqwest::get(url).await.unwrap();
+        (response.status(), response.text().await.unwrap())
+    }
+
+    fn uri_lines(playlist: &str) -> Vec<&str> {
+        playlist.lines().filter(|l| !l.starts_with('#')).collect()
+    }
+
+    #[test]
+    fn test_rewrite_playlist() {
+        let base = Url::parse("https://cdn.example.com/live/360p/index.m3u8?token=1").unwrap();
+        let playlist = "#EXTM3U
+#EXT-X-KEY:METHOD=AES-128,URI=\"https://keys.example.com/k1\",IV=0x1
+#EXT-X-KEY:METHOD=SAMPLE-AES,KEYFORMAT=\"com.apple.streamingkeydelivery\",URI=\"skd://k2\"
+#EXT-X-MAP:URI=\"init.mp4\"
+#EXTINF:6.0,
+segment1.ts
+
+#EXTINF:6.0,
+../shared/segment2.ts
+";
+
+        let rewritten = rewrite_playlist(playlist, &base, |url| format!("proxy[{}]", url));
+
+        assert_eq!(
+            rewritten,
+            "#EXTM3U
+#EXT-X-KEY:METHOD=AES-128,URI=\"proxy[https://keys.example.com/k1]\",IV=0x1
+#EXT-X-KEY:METHOD=SAMPLE-AES,KEYFORMAT=\"com.apple.streamingkeydelivery\",URI=\"skd://k2\"
+#EXT-X-MAP:URI=\"proxy[https://cdn.example.com/live/360p/init.mp4]\"
+#EXTINF:6.0,
+proxy[https://cdn.example.com/live/360p/segment1.ts]
+
+#EXTINF:6.0,
+proxy[https://cdn.example.com/live/shared/segment2.ts]
+"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_serves_stream_through_proxy() {
+        let origin = fake_origin(0).await;
+        let (proxy, _) = start_proxy().await;
+
+        let url = proxy.open(Some(2), master_url(origin));
+        assert!(url.starts_with("http://127.0.0.1:"));
+
+        let (status, master) = get(&url).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!master.contains(&origin.to_string()));
+        assert!(master.contains("URI=\"http://127.0.0.1:"));
+
+        let (_, media) = get(uri_lines(&master)[0]).await;
+        assert!(media.contains("#EXT-X-MAP:URI=\"http://127.0.0.1:"));
+
+        let (status, segment) = get(uri_lines(&media)[0]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(segment, "segment-bytes");
+
+        assert_eq!(
+            proxy.traffic(),
+            vec![ViewportTraffic {
+                viewport_index: 2,
+                bytes: (master.len() + media.len() + segment.len()) as u64,
+                requests: 3,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cdn_cookies_stay_with_the_route() {
+        let origin = fake_origin(0).await;
+        let (proxy, direct) = start_proxy().await;
+
+        let (_, master) = get(&proxy.open(Some(0), master_url(origin))).await;
+        get(uri_lines(&master)[0]).await;
+        get(&proxy.open(Some(1), master_url(origin))).await;
+
+        let cookies: Vec<_> = direct
+            .requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, cookie)| cookie.clone())
+            .collect();
+        assert_eq!(cookies, vec![None, Some(CDN_COOKIE.to_string()), None]);
+    }
+
+    #[tokio::test]
+    async fn test_refuses_origins_the_playlists_did_not_name() {
+        let origin = fake_origin(0).await;
+        let (proxy, _) = start_proxy().await;
+
+        let (_, master) = get(&proxy.open(Some(0), master_url(origin))).await;
+        let media_url = uri_lines(&master)[0];
+        let mut forged = Url::parse(media_url).unwrap();
+        forged.set_query(Some("u=https%3A%2F%2Fexample.com%2Fx.ts"));
+
+        assert_eq!(get(forged.as_str()).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(get(media_url).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_playlists_resolve_against_the_redirect_target() {
+        let origin = fake_origin(0).await;
+        let redirect = redirect_to(master_url(origin)).await;
+        let (proxy, _) = start_proxy().await;
+
+        let url = proxy.open(
+            Some(0),
+            Url::parse(&format!("http://{}/stream/master.m3u8", redirect)).unwrap(),
+        );
+        let (status, master) = get(&url).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, media) = get(uri_lines(&master)[0]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(get(uri_lines(&media)[0]).await.1, "segment-bytes");
+    }
+
+    #[tokio::test]
+    async fn test_head_is_forwarded_and_not_counted() {
+        let origin = fake_origin(0).await;
+        let (proxy, direct) = start_proxy().await;
+
+        let (_, master) = get(&proxy.open(Some(0), master_url(origin))).await;
+        let (_, media) = get(uri_lines(&master)[0]).await;
+        let traffic = proxy.traffic();
+
+        let response = reqwest::Client::new()
+            .head(uri_lines(&media)[0])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(proxy.traffic(), traffic);
+        assert_eq!(
+            direct.requests.lock().unwrap().last().unwrap().0,
+            Method::HEAD
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_failed_segments() {
+        let origin = fake_origin(SEGMENT_ATTEMPTS as usize - 1).await;
+        let (proxy, direct) = start_proxy().await;
+
+        let url = proxy.open(Some(0), master_url(origin));
+        let (_, master) = get(&url).await;
+        let (_, media) = get(uri_lines(&master)[0]).await;
+        let (status, segment) = get(uri_lines(&media)[0]).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(segment, "segment-bytes");
+        assert_eq!(
+            direct.requests.lock().unwrap().len(),
+            2 + SEGMENT_ATTEMPTS as usize
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_last_attempt() {
+        let origin = fake_origin(SEGMENT_ATTEMPTS as usize).await;
+        let (proxy, _) = start_proxy().await;
+
+        let url = proxy.open(Some(0), master_url(origin));
+        let (_, master) = get(&url).await;
+        let (_, media) = get(uri_lines(&master)[0]).await;
+        let (status, _) = get(uri_lines(&media)[0]).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_replaced_routes_are_refused() {
+        let origin = fake_origin(0).await;
+        let (proxy, _) = start_proxy().await;
+
+        let first = proxy.open(Some(1), master_url(origin));
+        let second = proxy.open(Some(1), master_url(origin));
+
+        assert_eq!(get(&first).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&second).await.0, StatusCode::OK);
+
+        let mut forged = Url::parse(&second).unwrap();
+        forged.set_path(&format!("/{}/x.ts", "0".repeat(32)));
+        forged.set_query(Some("u=https%3A%2F%2Fexample.com%2F"));
+        assert_eq!(get(forged.as_str()).await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_routes_without_a_viewport_stay_until_closed() {
+        let origin = fake_origin(0).await;
+        let (proxy, _) = start_proxy().await;
+
+        let first = proxy.open(None, master_url(origin));
+        let second = proxy.open(None, master_url(origin));
+        let viewport = proxy.open(Some(0), master_url(origin));
+
+        assert_eq!(get(&first).await.0, StatusCode::OK);
+        assert_eq!(get(&second).await.0, StatusCode::OK);
+
+        proxy.close(&first);
+        proxy.close(&viewport);
+        assert_eq!(get(&first).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&viewport).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&second).await.0, StatusCode::OK);
+
+        // Closing a route doesn't touch the one that replaced it
+        let replaced = proxy.open(Some(1), master_url(origin));
+        let current = proxy.open(Some(1), master_url(origin));
+        proxy.close(&replaced);
+        assert_eq!(get(&current).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_master_playlist_is_filtered_to_the_viewport_cap() {
+        let origin = fake_origin(0).await;
+        let quality = QualityState::default();
+        let mut settings = QualitySettings::default();
+        settings.caps.insert(
+            3,
+            QualityCap {
+                max_height: Some(720),
+                max_bitrate: None,
+            },
+        );
+        quality.restore(settings);
+        let (proxy, _) = start_proxy_with(quality).await;
+
+        let (_, capped) = get(&proxy.open(Some(3), master_url(origin))).await;
+        assert_eq!(uri_lines(&capped).len(), 1);
+        assert!(!capped.contains("1080p"));
+
+        let (_, uncapped) = get(&proxy.open(Some(0), master_url(origin))).await;
+        assert_eq!(uri_lines(&uncapped).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_variants_over_the_limit_are_refused() {
+        let origin = fake_origin(0).await;
+        let quality = QualityState::default();
+        let (proxy, _) = start_proxy_with(quality.clone()).await;
+
+        let url = proxy.open(Some(0), master_url(origin));
+        let (_, master) = get(&url).await;
+        let hd = uri_lines(&master)[1];
+        assert_eq!(get(hd).await.0, StatusCode::NOT_FOUND);
+
+        // The cap drops after the player loaded the master playlist, and
+        // holds once the player reloads it
+        let mut settings = QualitySettings::default();
+        settings.caps.insert(
+            0,
+            QualityCap {
+                max_height: None,
+                max_bitrate: Some(1_000_000),
+            },
+        );
+        quality.restore(settings);
+        assert_eq!(get(hd).await.0, StatusCode::NOT_FOUND);
+
+        get(&url).await;
+        assert_eq!(get(hd).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(get(uri_lines(&master)[0]).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_another_viewport_starting_keeps_loaded_variants_playing() {
+        let origin = fake_origin(0).await;
+        let quality = QualityState::default();
+        quality.restore(QualitySettings {
+            bandwidth_budget: Some(8_000_000),
+            ..Default::default()
+        });
+        let (proxy, _) = start_proxy_with(quality).await;
+
+        let (_, first) = get(&proxy.open(Some(0), master_url(origin))).await;
+        let hd = uri_lines(&first)[1];
+        assert_eq!(get(hd).await.0, StatusCode::NOT_FOUND);
+
+        // Splitting the budget rules the HD variant out for new players...
+        let (_, second) = get(&proxy.open(Some(1), master_url(origin))).await;
+        assert_eq!(uri_lines(&second).len(), 1);
+
+        // ...but the first keeps reloading the one it was given
+        assert_eq!(get(hd).await.0, StatusCode::NOT_FOUND);
+    }
+}
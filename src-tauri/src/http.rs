@@ -0,0 +1,124 @@
+use reqwest::header::{HeaderValue, COOKIE, SET_COOKIE};
+use reqwest::{Request, RequestBuilder, Response};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::{cookies, session_store, unix_now, AuthSession, AuthState};
+
+const USER_AGENT: &str = concat!("loonieVision/", env!("CARGO_PKG_VERSION"));
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// The HTTP client every backend request to CBC goes through, managed as
+/// Tauri state so concurrent requests share its connection pool.
+///
+/// There is no client-wide cookie jar: each account has its own cookies, so
+/// they are scoped per request from the account's session instead.
+pub struct HttpClient {
+    client: reqwest::Client,
+}
+
+impl HttpClient {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .build()
+            .expect("failed to build HTTP client");
+        Self { client }
+    }
+
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Sends `request` with the cookies `account` holds for its URL, then
+    /// applies any `Set-Cookie` in the response back to that account.
+    /// Without an account the request goes out anonymously.
+    pub async fn send_as(
+        &self,
+        app: &AppHandle,
+        account: Option<&str>,
+        request: RequestBuilder,
+    ) -> reqwest::Result<Response> {
+        let mut request = request.build()?;
+
+        if let Some(name) = account {
+            let header = app
+                .state::<AuthState>()
+                .accounts
+                .lock()
+                .unwrap()
+                .get(name)
+                .and_then(|session| cookies::cookie_header_for(session, request.url()));
+            insert_cookie_header(&mut request, header);
+        }
+
+        let response = self.client.execute(request).await?;
+        if let Some(name) = account {
+            store_set_cookies(app, name, &response);
+        }
+        Ok(response)
+    }
+
+    /// Like [`send_as`](Self::send_as), for a session that isn't one of the
+    /// accounts yet: its cookies go out and the response's `Set-Cookie`
+    /// comes back to it.
+    pub async fn send_with(
+        &self,
+        session: &mut AuthSession,
+        request: RequestBuilder,
+    ) -> reqwest::Result<Response> {
+        let mut request = request.build()?;
+        insert_cookie_header(
+            &mut request,
+            cookies::cookie_header_for(session, request.url()),
+        );
+
+        let response = self.client.execute(request).await?;
+        cookies::apply_set_cookies(session, response.url(), set_cookies(&response), unix_now());
+        Ok(response)
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn insert_cookie_header(request: &mut Request, header: Option<String>) {
+    if let Some(value) = header.and_then(|h| HeaderValue::from_str(&h).ok()) {
+        request.headers_mut().insert(COOKIE, value);
+    }
+}
+
+fn set_cookies(response: &Response) -> Vec<&str> {
+    response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .collect()
+}
+
+fn store_set_cookies(app: &AppHandle, account: &str, response: &Response) {
+    let set_cookies = set_cookies(response);
+    if set_cookies.is_empty() {
+        return;
+    }
+
+    let state = app.state::<AuthState>();
+    let mut accounts = state.accounts.lock().unwrap();
+    let Some(session) = accounts.get_mut(account) else {
+        return;
+    };
+    if cookies::apply_set_cookies(session, response.url(), set_cookies, unix_now()) {
+        if let Err(e) = session_store::persist(app, &accounts) {
+            eprintln!("Failed to persist refreshed session: {}", e);
+        }
+    }
+}
@@ -1,83 +1,376 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use tauri::{Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri::{Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_updater::UpdaterExt;
 
+mod account;
+mod accounts;
+mod app_store;
+mod auth_flow;
+mod calendar;
+mod catalog_cache;
+mod catalog_sections;
+mod catalog_watcher;
+mod cookie_import;
+mod cookies;
+mod error;
+mod hls;
+mod hls_proxy;
+mod http;
+mod quality;
+mod reminders;
+mod schedule;
+mod search;
+mod session_monitor;
+mod session_store;
+mod stream_status;
+mod validation;
+
+pub use account::{AccountProfile, SubscriptionTier};
+pub use accounts::{AccountSummary, Accounts};
+pub use catalog_cache::CatalogState;
+pub use catalog_sections::{CatalogSection, SectionError};
+pub use catalog_watcher::{CatalogChange, CatalogWatcher};
+pub use cookie_import::ImportSource;
+pub use cookies::CookieMeta;
+pub use error::LoonieError;
+pub use hls::{IFramePlaylist, MasterPlaylist, Rendition, Resolution, Variant};
+pub use hls_proxy::{HlsProxy, ViewportTraffic};
+pub use http::HttpClient;
+pub use quality::{QualityCap, QualitySettings, QualityState};
+pub use reminders::{Reminder, ReminderState};
+pub use schedule::{Schedule, ScheduleDay, ScheduleEvent, ScheduleGap, SportSchedule};
+pub use search::{Highlight, SearchResult, StreamQuery};
+pub use stream_status::StreamStatus;
+pub use validation::{Explanation, RecoveryHint, ValidationFailure};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthSession {
+    /// Cookie values by name, for reading tokens out of the session.
     pub cookies: HashMap<String, String>,
+    /// The cookies requests are sent with, each scoped to its domain and path.
+    #[serde(default)]
+    pub cookie_meta: Vec<CookieMeta>,
     pub user_id: Option<String>,
+    #[serde(default)]
+    pub profile: Option<AccountProfile>,
     pub expires_at: i64,
 }
 
+impl AuthSession {
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// What the webview is allowed to know about the session: never the
+/// cookies themselves.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub signed_in: bool,
+    pub user_id: Option<String>,
+    pub display_name: Option<String>,
+    pub expires_at: Option<i64>,
+}
+
+impl SessionSummary {
+    pub fn from_session(session: Option<&AuthSession>) -> Self {
+        match session {
+            Some(session) => Self {
+                signed_in: true,
+                user_id: session.user_id.clone(),
+                display_name: session
+                    .profile
+                    .as_ref()
+                    .and_then(|p| p.display_name.clone()),
+                expires_at: Some(session.expires_at),
+            },
+            None => Self {
+                signed_in: false,
+                user_id: None,
+                display_name: None,
+                expires_at: None,
+            },
+        }
+    }
+}
+
+/// Current time as seconds since the Unix epoch.
+pub(crate) fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
 #[derive(Clone)]
 pub struct AuthState {
-    pub session: Arc<Mutex<Option<AuthSession>>>,
+    pub accounts: Arc<Mutex<Accounts>>,
     pub auth_webview_label: Arc<Mutex<Option<String>>>,
+    /// Viewport index -> account name, for viewports pinned to an account
+    /// other than the active one.
+    pub viewport_accounts: Arc<Mutex<HashMap<u32, String>>>,
 }
 
 impl AuthState {
     pub fn new() -> Self {
         Self {
-            session: Arc::new(Mutex::new(None)),
+            accounts: Arc::new(Mutex::new(Accounts::default())),
             auth_webview_label: Arc::new(Mutex::new(None)),
+            viewport_accounts: Arc::new(Mutex::new(HashMap::new())),
         }
     }
+
+    pub fn active_session(&self) -> Option<AuthSession> {
+        self.accounts.lock().unwrap().active().cloned()
+    }
+
+    pub fn active_account(&self) -> Option<String> {
+        self.accounts
+            .lock()
+            .unwrap()
+            .active_name()
+            .map(|n| n.to_string())
+    }
+
+    /// The account a viewport plays with: its pinned account while that
+    /// account is still signed in, the active account otherwise.
+    pub fn account_for_viewport(&self, viewport_index: Option<u32>) -> Option<String> {
+        let accounts = self.accounts.lock().unwrap();
+        viewport_index
+            .and_then(|index| self.viewport_accounts.lock().unwrap().get(&index).cloned())
+            .filter(|name| accounts.get(name).is_some())
+            .or_else(|| accounts.active_name().map(|n| n.to_string()))
+    }
+
+    /// Unpins every viewport pinned to `name`, once that account is gone.
+    pub fn unpin_account(&self, name: &str) {
+        self.viewport_accounts
+            .lock()
+            .unwrap()
+            .retain(|_, pinned| *pinned != name);
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Default for AuthState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Id of the stream behind the link the app was launched with, kept until
+/// the frontend has mounted and takes it.
+#[derive(Default)]
+pub struct PendingStreamLink(pub Mutex<Option<String>>);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct StreamInfo {
     pub id: String,
     pub title: String,
     pub description: String,
     pub sport: String,
-    pub status: String,
+    pub status: StreamStatus,
     pub start_time: String,
     pub end_time: Option<String>,
     pub thumbnail_url: String,
     pub stream_url: String,
     pub requires_auth: bool,
     pub is_premium: bool,
+    /// Whether CBC offers the stream on demand.
+    #[serde(default)]
+    pub vod_enabled: bool,
+    /// Slug of the GEM section the stream was listed in.
+    #[serde(default)]
+    pub section: String,
+}
+
+#[cfg(test)]
+impl StreamInfo {
+    /// An upcoming hockey stream for tests to adjust with struct update syntax.
+    pub(crate) fn test(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: format!("Stream {}", id),
+            description: String::new(),
+            sport: "Hockey".to_string(),
+            status: StreamStatus::Upcoming,
+            start_time: "2026-02-10T14:00:00Z".to_string(),
+            end_time: None,
+            thumbnail_url: String::new(),
+            stream_url: format!("https://gem.cbc.ca/media/{}", id),
+            requires_auth: false,
+            is_premium: false,
+            vod_enabled: false,
+            section: "olympics".to_string(),
+        }
+    }
+}
+
+/// The stream catalog. `stale` is set when CBC could not be reached and
+/// this is the last catalog fetched, at `fetched_at`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct StreamCatalog {
+    pub streams: Vec<StreamInfo>,
+    pub stale: bool,
+    pub fetched_at: i64,
+    /// Sections that failed this time, in section order.
+    #[serde(skip_deserializing)]
+    pub section_errors: Vec<SectionError>,
 }
 
 mod commands {
     use super::*;
-    use tauri::Emitter;
+    use auth_flow::AuthFlowEvent;
+    use tauri::webview::PageLoadEvent;
 
     #[tauri::command]
     pub async fn check_auth_status(
+        app: tauri::AppHandle,
         state: State<'_, AuthState>,
-    ) -> Result<Option<AuthSession>, String> {
-        let session = state.session.lock().unwrap();
-        Ok(session.clone())
+    ) -> Result<SessionSummary, LoonieError> {
+        let mut accounts = state.accounts.lock().unwrap();
+
+        // Sessions can run out while the app is open
+        if !accounts.remove_expired(unix_now()).is_empty() {
+            session_store::persist(&app, &accounts)?;
+        }
+        Ok(SessionSummary::from_session(accounts.active()))
     }
 
+    /// Signs out of the active account, on CBC's side as well as ours, and
+    /// clears the CBC login from the webview so the next sign-in starts
+    /// fresh. Any other accounts stay signed in and the next one becomes
+    /// active.
     #[tauri::command]
-    pub async fn set_auth_session(
-        session: AuthSession,
+    pub async fn logout(
+        app: tauri::AppHandle,
         state: State<'_, AuthState>,
-    ) -> Result<(), String> {
-        let mut sess = state.session.lock().unwrap();
-        *sess = Some(session);
-        Ok(())
+        http: State<'_, HttpClient>,
+    ) -> Result<(), LoonieError> {
+        let Some(name) = state.active_account() else {
+            return Ok(());
+        };
+
+        // Best effort: the local sign-out goes ahead even if CBC can't be reached
+        let request = http.client().get(auth_flow::LOGOUT_URL);
+        if let Err(e) = http.send_as(&app, Some(&name), request).await {
+            eprintln!("Failed to sign out from CBC: {}", e);
+        }
+
+        {
+            let mut accounts = state.accounts.lock().unwrap();
+            accounts.remove(&name);
+            state.unpin_account(&name);
+            session_store::persist(&app, &accounts)?;
+        }
+
+        // The account is gone either way, so the frontend hears about it
+        // even if the webview's copy of the login couldn't be cleared
+        let cleared = cookies::clear_webview_cookies(&app);
+        let _ = app.emit(
+            "cbc-auth-logged-out",
+            auth_flow::LoggedOutPayload { account: name },
+        );
+        cleared.map(|_| ())
+    }
+
+    #[tauri::command]
+    pub async fn list_accounts(
+        state: State<'_, AuthState>,
+    ) -> Result<Vec<AccountSummary>, LoonieError> {
+        Ok(state.accounts.lock().unwrap().summaries())
+    }
+
+    #[tauri::command]
+    pub async fn switch_account(
+        app: tauri::AppHandle,
+        state: State<'_, AuthState>,
+        name: String,
+    ) -> Result<SessionSummary, LoonieError> {
+        let mut accounts = state.accounts.lock().unwrap();
+        accounts.switch(&name)?;
+        session_store::persist(&app, &accounts)?;
+        Ok(SessionSummary::from_session(accounts.active()))
+    }
+
+    #[tauri::command]
+    pub async fn remove_account(
+        app: tauri::AppHandle,
+        state: State<'_, AuthState>,
+        name: String,
+    ) -> Result<(), LoonieError> {
+        let mut accounts = state.accounts.lock().unwrap();
+        accounts
+            .remove(&name)
+            .ok_or_else(|| LoonieError::UnknownAccount { name: name.clone() })?;
+        state.unpin_account(&name);
+        session_store::persist(&app, &accounts)
     }
 
+    /// Pins a viewport to an account so its streams keep playing with that
+    /// account's entitlements. `None` unpins it back to the active account.
     #[tauri::command]
-    pub async fn logout(state: State<'_, AuthState>) -> Result<(), String> {
-        let mut session = state.session.lock().unwrap();
-        *session = None;
+    pub async fn pin_viewport_account(
+        state: State<'_, AuthState>,
+        viewport_index: u32,
+        name: Option<String>,
+    ) -> Result<(), LoonieError> {
+        let accounts = state.accounts.lock().unwrap();
+        let mut pinned = state.viewport_accounts.lock().unwrap();
+        match name {
+            Some(name) => {
+                if accounts.get(&name).is_none() {
+                    return Err(LoonieError::UnknownAccount { name });
+                }
+                pinned.insert(viewport_index, name);
+            }
+            None => {
+                pinned.remove(&viewport_index);
+            }
+        }
         Ok(())
     }
 
     #[tauri::command]
-    pub async fn start_cbc_auth(
+    pub async fn get_account_profile(
         app: tauri::AppHandle,
         state: State<'_, AuthState>,
-    ) -> Result<(), String> {
-        const AUTH_URL: &str = "https://www.cbc.ca/account/login?returnto=https%3A%2F%2Fwww.cbc.ca%2F&referrer=https%3A%2F%2Fwww.cbc.ca%2F";
+        http: State<'_, HttpClient>,
+    ) -> Result<Option<AccountProfile>, LoonieError> {
+        let (name, mut session) = {
+            let accounts = state.accounts.lock().unwrap();
+            match (accounts.active_name(), accounts.active()) {
+                (Some(name), Some(session)) => (name.to_string(), session.clone()),
+                _ => return Ok(None),
+            }
+        };
+        if session.profile.is_some() {
+            return Ok(session.profile);
+        }
+
+        // Sessions from before profiles were captured at sign-in
+        let profile = account::resolve_profile(&http, &mut session).await;
+        let mut accounts = state.accounts.lock().unwrap();
+        if let Some(current) = accounts.get_mut(&name) {
+            session.user_id = profile.as_ref().and_then(|p| p.user_id.clone());
+            session.profile = profile.clone();
+            *current = session;
+            session_store::persist(&app, &accounts)?;
+        }
+
+        Ok(profile)
+    }
 
+    #[tauri::command]
+    pub async fn start_cbc_auth(
+        app: tauri::AppHandle,
+        state: State<'_, AuthState>,
+        timeout_secs: Option<u64>,
+        account_name: Option<String>,
+    ) -> Result<(), LoonieError> {
         // Check if auth window already exists
         if let Some(label) = state.auth_webview_label.lock().unwrap().as_ref() {
             if let Some(window) = app.get_webview_window(label) {
@@ -88,11 +381,18 @@ mod commands {
 
         // Create a new webview window for authentication
         let label = "cbc_auth_window".to_string();
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let nav_tx = tx.clone();
+        let load_tx = tx.clone();
+
+        // A fresh cookie jar, so adding an account doesn't just pick up the
+        // CBC login of the one already signed in
+        let incognito = !state.accounts.lock().unwrap().is_empty();
 
-        let _webview = WebviewWindowBuilder::new(
+        let webview = WebviewWindowBuilder::new(
             &app,
             &label,
-            WebviewUrl::External(AUTH_URL.parse().unwrap()),
+            WebviewUrl::External(auth_flow::AUTH_URL.parse().unwrap()),
         )
         .title("Sign in with CBC")
         .inner_size(500.0, 700.0)
@@ -100,104 +400,81 @@ mod commands {
         .resizable(true)
         .minimizable(false)
         .maximizable(false)
+        .incognito(incognito)
+        .on_navigation(move |url| {
+            let _ = nav_tx.send(AuthFlowEvent::Navigated(url.clone()));
+            true
+        })
+        .on_page_load(move |_window, payload| {
+            if payload.event() == PageLoadEvent::Finished {
+                let _ = load_tx.send(AuthFlowEvent::PageLoaded(payload.url().clone()));
+            }
+        })
         .build()
-        .map_err(|e| format!("Failed to create auth window: {}", e))?;
+        .map_err(|e| LoonieError::Internal {
+            message: format!("Failed to create auth window: {}", e),
+        })?;
+
+        webview.on_window_event(move |event| {
+            if let tauri::WindowEvent::Destroyed = event {
+                let _ = tx.send(AuthFlowEvent::WindowClosed);
+            }
+        });
 
         // Store the label
         *state.auth_webview_label.lock().unwrap() = Some(label.clone());
 
-        // Start polling for completion
+        let timeout = timeout_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(auth_flow::DEFAULT_TIMEOUT);
         let app_handle = app.clone();
         let state_clone = state.inner().clone();
         tauri::async_runtime::spawn(async move {
-            poll_for_auth_completion(app_handle, state_clone, label).await;
+            auth_flow::drive(app_handle, state_clone, label, account_name, rx, timeout).await;
         });
 
         Ok(())
     }
 
-    async fn poll_for_auth_completion(app: tauri::AppHandle, state: AuthState, label: String) {
-        const LANDING_URL: &str = "https://www.cbc.ca/account/landing";
-        const MAX_ATTEMPTS: u32 = 600; // 5 minutes at 500ms intervals
-
-        for _ in 0..MAX_ATTEMPTS {
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-            // Check if window still exists
-            let Some(window) = app.get_webview_window(&label) else {
-                // Window was closed by user
-                let _ = app.emit("cbc-auth-cancelled", ());
-                *state.auth_webview_label.lock().unwrap() = None;
-                return;
-            };
-
-            // Get current URL
-            if let Ok(url) = window.url() {
-                let url_str = url.to_string();
-
-                if url_str.starts_with(LANDING_URL) {
-                    // User has logged in, extract cookies
-                    if let Ok(cookies) = window.cookies_for_url(url) {
-                        let cookie_map: HashMap<String, String> = cookies
-                            .into_iter()
-                            .filter_map(|c| {
-                                let name = c.name().to_string();
-                                let value = c.value().to_string();
-                                if !name.is_empty() && !value.is_empty() {
-                                    Some((name, value))
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect();
-
-                        if !cookie_map.is_empty() {
-                            // Create session
-                            let session = AuthSession {
-                                cookies: cookie_map,
-                                user_id: None, // Could extract from cookies if available
-                                expires_at: std::time::SystemTime::now()
-                                    .duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_secs() as i64
-                                    + 86400, // 24 hours
-                            };
-
-                            // Store session
-                            *state.session.lock().unwrap() = Some(session.clone());
-
-                            // Close window
-                            let _ = window.close();
-                            *state.auth_webview_label.lock().unwrap() = None;
-
-                            // Emit success event
-                            let _ = app.emit("cbc-auth-success", session);
-                            return;
-                        }
-                    }
-
-                    // Failed to extract cookies
-                    let _ = window.close();
-                    *state.auth_webview_label.lock().unwrap() = None;
-                    let _ = app.emit("cbc-auth-error", "Failed to extract session cookies");
-                    return;
-                }
-            }
-        }
+    /// Signs in from cookies exported from a browser, for when the auth
+    /// webview can't complete the login.
+    #[tauri::command]
+    pub async fn import_session(
+        app: tauri::AppHandle,
+        state: State<'_, AuthState>,
+        http: State<'_, HttpClient>,
+        catalog: State<'_, CatalogState>,
+        source: ImportSource,
+        account_name: Option<String>,
+    ) -> Result<SessionSummary, LoonieError> {
+        // Reads and copies browser databases, so it stays off the async workers
+        let mut session = tauri::async_runtime::spawn_blocking(move || {
+            cookie_import::session_from_source(&source, unix_now())
+        })
+        .await
+        .map_err(|e| LoonieError::Internal {
+            message: format!("Cookie import task failed: {}", e),
+        })??;
+
+        // Any stream CBC knows about will do to ask the validation API
+        let probe_media = catalog.latest.lock().unwrap().as_ref().and_then(|latest| {
+            latest
+                .streams
+                .iter()
+                .find_map(|stream| validation::media_id(&stream.stream_url))
+        });
+        account::verify_session(&http, &mut session, probe_media).await?;
 
-        // Timeout - close window
-        if let Some(window) = app.get_webview_window(&label) {
-            let _ = window.close();
-        }
-        *state.auth_webview_label.lock().unwrap() = None;
-        let _ = app.emit("cbc-auth-timeout", ());
+        let summary = auth_flow::store_session(&app, &state, account_name, session).await;
+        let _ = app.emit("cbc-auth-success", summary.clone());
+        Ok(summary)
     }
 
     #[tauri::command]
     pub async fn cancel_cbc_auth(
         app: tauri::AppHandle,
         state: State<'_, AuthState>,
-    ) -> Result<(), String> {
+    ) -> Result<(), LoonieError> {
         if let Some(label) = state.auth_webview_label.lock().unwrap().take() {
             if let Some(window) = app.get_webview_window(&label) {
                 let _ = window.close();
@@ -214,6 +491,20 @@ mod commands {
     #[derive(Debug, Deserialize)]
     struct Lineups {
         results: Vec<Lineup>,
+        #[serde(rename = "totalPages", default)]
+        total_pages: Option<usize>,
+        #[serde(rename = "totalCount", alias = "totalItems", default)]
+        total_count: Option<usize>,
+    }
+
+    impl Lineups {
+        /// Number of catalog pages, if the response says, up to
+        /// `MAX_CATALOG_PAGES` however many it claims.
+        fn page_count(&self, page_size: usize) -> Option<usize> {
+            self.total_pages
+                .or_else(|| self.total_count.map(|count| count.div_ceil(page_size)))
+                .map(|count| count.min(MAX_CATALOG_PAGES))
+        }
     }
 
     #[derive(Debug, Deserialize)]
@@ -245,6 +536,40 @@ mod commands {
         is_vod_enabled: bool,
         #[serde(rename = "idMedia", default)]
         id_media: Option<i64>,
+        /// Running time in seconds.
+        #[serde(default)]
+        duration: Option<i64>,
+        #[serde(rename = "broadcastStartDate", default)]
+        broadcast_start: Option<String>,
+        #[serde(rename = "broadcastEndDate", default)]
+        broadcast_end: Option<String>,
+    }
+
+    fn parse_catalog_date(value: &str) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(value)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+    }
+
+    /// When an item stops airing: the end of its broadcast window, else its
+    /// start plus its duration, else its start plus what the sport usually
+    /// takes.
+    fn item_end_time(item: &LineupItem, start: DateTime<Utc>, sport: &str) -> DateTime<Utc> {
+        if let Some(end) = item.broadcast_end.as_deref().and_then(parse_catalog_date) {
+            return end;
+        }
+
+        match item.duration.filter(|seconds| *seconds > 0) {
+            Some(seconds) => {
+                let from = item
+                    .broadcast_start
+                    .as_deref()
+                    .and_then(parse_catalog_date)
+                    .unwrap_or(start);
+                from + chrono::Duration::seconds(seconds)
+            }
+            None => start + stream_status::typical_duration(sport),
+        }
     }
 
     #[derive(Debug, Deserialize)]
@@ -260,66 +585,459 @@ mod commands {
         url: String,
     }
 
+    /// Streams of every enabled catalog section.
     #[tauri::command]
-    pub async fn fetch_olympic_streams(
-        _cookies: std::collections::HashMap<String, String>,
-    ) -> Result<Vec<StreamInfo>, String> {
-        const CATALOG_URL: &str =
-            "https://services.radio-canada.ca/ott/catalog/v2/gem/section/olympics";
-
-        let client = reqwest::Client::new();
-        let mut all_streams = Vec::new();
-        let mut seen_ids: HashSet<String> = HashSet::new();
-        let mut page_number = 1;
-        let page_size = 6;
+    pub async fn fetch_streams(app: tauri::AppHandle) -> Result<StreamCatalog, LoonieError> {
+        load_catalog(&app).await
+    }
 
-        loop {
-            let url = format!(
-                "{}?device=web&pageSize={}&pageNumber={}",
-                CATALOG_URL, page_size, page_number
-            );
+    #[tauri::command]
+    pub async fn list_catalog_sections(
+        catalog: State<'_, CatalogState>,
+    ) -> Result<Vec<CatalogSection>, LoonieError> {
+        Ok(catalog_sections::available(&catalog.sections()))
+    }
 
-            let response = client
-                .get(&url)
-                .header("Accept", "application/json")
-                .send()
-                .await
-                .map_err(|e| format!("Failed to fetch catalog: {}", e))?;
+    /// Changes which sections make up the catalog, by slug, and refreshes
+    /// it. No slugs at all means the Olympics section only.
+    #[tauri::command]
+    pub async fn set_catalog_sections(
+        app: tauri::AppHandle,
+        catalog: State<'_, CatalogState>,
+        watcher: State<'_, CatalogWatcher>,
+        slugs: Vec<String>,
+    ) -> Result<Vec<CatalogSection>, LoonieError> {
+        let sections = catalog_sections::normalize(&slugs)?;
+        catalog_sections::save(&app, &sections)?;
+
+        *catalog.sections.lock().unwrap() = sections.clone();
+        *catalog.latest.lock().unwrap() = None;
+        watcher.refresh();
+        Ok(catalog_sections::available(&sections))
+    }
+
+    /// Searches the catalog last fetched, fetching it first if need be.
+    /// Calendar days are the user's local ones.
+    #[tauri::command]
+    pub async fn search_streams(
+        app: tauri::AppHandle,
+        query: StreamQuery,
+    ) -> Result<Vec<SearchResult>, LoonieError> {
+        let catalog = latest_catalog(&app).await?;
+        Ok(search::search(&catalog.streams, &query, &chrono::Local))
+    }
+
+    /// The catalog by day and sport in `time_zone`, an IANA name such as
+    /// "America/Toronto".
+    #[tauri::command]
+    pub async fn get_schedule(
+        app: tauri::AppHandle,
+        time_zone: String,
+    ) -> Result<Schedule, LoonieError> {
+        let tz: chrono_tz::Tz = time_zone
+            .parse()
+            .map_err(|_| LoonieError::InvalidTimeZone { name: time_zone })?;
+        let catalog = latest_catalog(&app).await?;
+        Ok(schedule::build_schedule(&catalog.streams, tz, Utc::now()))
+    }
+
+    /// Asks where to save an iCalendar file of `streams` and writes it.
+    /// Returns the path written, or `None` if the user cancelled.
+    #[tauri::command]
+    pub async fn export_calendar(
+        app: tauri::AppHandle,
+        streams: Vec<StreamInfo>,
+    ) -> Result<Option<String>, LoonieError> {
+        use tauri_plugin_dialog::DialogExt;
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        app.dialog()
+            .file()
+            .set_title("Export schedule")
+            .set_file_name("olympics.ics")
+            .add_filter("iCalendar", &["ics"])
+            .save_file(move |file| {
+                let _ = tx.send(file);
+            });
+        let Some(file) = rx.await.map_err(|e| LoonieError::Internal {
+            message: format!("Save dialog closed without answering: {}", e),
+        })?
+        else {
+            return Ok(None);
+        };
+        let path = file.into_path().map_err(LoonieError::storage)?;
 
-            if !response.status().is_success() {
-                return Err(format!(
-                    "Catalog API returned status: {}",
-                    response.status()
-                ));
+        tokio::fs::write(&path, calendar::to_ics(&streams, Utc::now()))
+            .await
+            .map_err(LoonieError::storage)?;
+        Ok(Some(path.to_string_lossy().into_owned()))
+    }
+
+    /// The stream behind the link the app was launched with, the first time
+    /// it's asked for. That link is read before the frontend listens for
+    /// `open-stream`, so the frontend asks once it has mounted.
+    #[tauri::command]
+    pub async fn take_stream_link(
+        app: tauri::AppHandle,
+        pending: State<'_, PendingStreamLink>,
+    ) -> Result<Option<StreamInfo>, LoonieError> {
+        let Some(id) = pending.0.lock().unwrap().take() else {
+            return Ok(None);
+        };
+        stream_for_link(&app, &id).await
+    }
+
+    /// Notifies `lead_minutes` (by default 10, from 1 up to a day) before an
+    /// upcoming `stream` starts, and again when it goes live.
+    #[tauri::command]
+    pub async fn add_reminder(
+        app: tauri::AppHandle,
+        state: State<'_, ReminderState>,
+        stream: StreamInfo,
+        lead_minutes: Option<i64>,
+    ) -> Result<Vec<Reminder>, LoonieError> {
+        if stream.status != StreamStatus::Upcoming {
+            return Err(LoonieError::ReminderNotUpcoming {
+                stream_id: stream.id,
+            });
+        }
+        let lead_minutes = lead_minutes.unwrap_or(reminders::DEFAULT_LEAD_MINUTES);
+        state.update(&app, |r| r.add(Reminder::new(&stream, lead_minutes)))
+    }
+
+    #[tauri::command]
+    pub async fn cancel_reminder(
+        app: tauri::AppHandle,
+        state: State<'_, ReminderState>,
+        stream_id: String,
+    ) -> Result<Vec<Reminder>, LoonieError> {
+        state.update(&app, |r| {
+            r.cancel(&stream_id);
+        })
+    }
+
+    #[tauri::command]
+    pub async fn list_reminders(
+        state: State<'_, ReminderState>,
+    ) -> Result<Vec<Reminder>, LoonieError> {
+        Ok(state.list())
+    }
+
+    #[tauri::command]
+    pub async fn set_catalog_watch_interval(
+        watcher: State<'_, CatalogWatcher>,
+        seconds: u64,
+    ) -> Result<(), LoonieError> {
+        watcher.set_interval(std::time::Duration::from_secs(seconds));
+        Ok(())
+    }
+
+    /// The current catalog, or the cached one when CBC can't be reached.
+    pub(crate) async fn load_catalog(app: &tauri::AppHandle) -> Result<StreamCatalog, LoonieError> {
+        let catalog = fetch_catalog(app).await?;
+        *app.state::<CatalogState>().latest.lock().unwrap() = Some(catalog.clone());
+        Ok(catalog)
+    }
+
+    /// The catalog last fetched, fetching it first if need be.
+    async fn latest_catalog(app: &tauri::AppHandle) -> Result<StreamCatalog, LoonieError> {
+        let latest = app.state::<CatalogState>().latest.lock().unwrap().clone();
+        match latest {
+            Some(catalog) => Ok(catalog),
+            None => load_catalog(app).await,
+        }
+    }
+
+    async fn fetch_catalog(app: &tauri::AppHandle) -> Result<StreamCatalog, LoonieError> {
+        let catalog = app.state::<CatalogState>();
+        let sections = catalog.sections();
+
+        let mut pages = Vec::new();
+        let mut section_errors = Vec::new();
+        let mut cached = None;
+        for (section, result) in fetch_sections(app, &sections).await {
+            match result {
+                Ok(section_pages) => pages.extend(
+                    section_pages
+                        .into_iter()
+                        .map(|(url, page)| (section.clone(), url, page)),
+                ),
+                // A section that failed keeps its pages from the last complete
+                // catalog rather than dropping its streams from the sidebar
+                Err(error) => {
+                    let (saved, _) =
+                        cached.get_or_insert_with(|| cached_pages(&catalog).unwrap_or_default());
+                    let (section_pages, rest) = std::mem::take(saved)
+                        .into_iter()
+                        .partition(|(saved_section, _, _)| *saved_section == section);
+                    *saved = rest;
+                    pages.extend(section_pages);
+                    section_errors.push(SectionError { section, error });
+                }
             }
+        }
 
-            let catalog: CatalogResponse = response
-                .json()
-                .await
-                .map_err(|e| format!("Failed to parse catalog response: {}", e))?;
+        let fetched_at = unix_now();
+        if section_errors.is_empty() {
+            let cache = {
+                let mut cache = catalog.cache.lock().unwrap();
+                cache.set_snapshot(
+                    pages.iter().map(|(_, url, _)| url.clone()).collect(),
+                    fetched_at,
+                );
+                cache.clone()
+            };
+            // Written off the async runtime, and without holding the lock
+            let app = app.clone();
+            match tokio::task::spawn_blocking(move || catalog_cache::save(&app, &cache)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => eprintln!("Failed to save catalog cache: {}", e),
+                Err(e) => eprintln!("Catalog cache save task failed: {}", e),
+            }
+        } else if pages.is_empty() {
+            return Err(section_errors.swap_remove(0).error);
+        }
 
-            let page_streams = convert_lineups_to_streams(&catalog.lineups.results, &mut seen_ids);
-            all_streams.extend(page_streams);
+        for failure in &section_errors {
+            eprintln!(
+                "Failed to fetch catalog section {}, serving it from cache: {}",
+                failure.section, failure.error
+            );
+        }
+        // Nothing fetched at all: the whole catalog is the saved one
+        let stale = section_errors.len() == sections.len();
+        let pages: Vec<_> = pages
+            .into_iter()
+            .map(|(section, _, page)| (section, page))
+            .collect();
+        Ok(StreamCatalog {
+            streams: streams_from_pages(&pages, Utc::now()),
+            stale,
+            fetched_at: match cached {
+                Some((_, cached_at)) if stale => cached_at,
+                _ => fetched_at,
+            },
+            section_errors,
+        })
+    }
 
-            // Check if we've reached the last page
-            if catalog.lineups.results.len() < page_size {
-                break;
+    /// Fetches every section at once, each on its own so one that fails
+    /// doesn't take the others with it. Results are in `sections` order.
+    async fn fetch_sections(
+        app: &tauri::AppHandle,
+        sections: &[String],
+    ) -> Vec<(String, Result<CatalogPages, LoonieError>)> {
+        let account = app.state::<AuthState>().active_account();
+        let tasks: Vec<_> = sections
+            .iter()
+            .map(|section| {
+                let app = app.clone();
+                let account = account.clone();
+                let section = section.clone();
+                tokio::spawn(async move {
+                    let http = app.state::<HttpClient>();
+                    let catalog = app.state::<CatalogState>();
+                    fetch_catalog_pages(&app, &http, &catalog, account.as_deref(), &section).await
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(tasks.len());
+        for (section, task) in sections.iter().zip(tasks) {
+            let result = task.await.unwrap_or_else(|e| {
+                Err(LoonieError::Internal {
+                    message: format!("Catalog section task failed: {}", e),
+                })
+            });
+            results.push((section.clone(), result));
+        }
+        results
+    }
+
+    /// `(section, url, page)` for each page of the last complete catalog,
+    /// and when it was fetched. `None` if there isn't one or it no longer
+    /// parses.
+    fn cached_pages(
+        catalog: &CatalogState,
+    ) -> Option<(Vec<(String, String, CatalogResponse)>, i64)> {
+        let cache = catalog.cache.lock().unwrap();
+        let (cached, fetched_at) = cache.snapshot()?;
+        let pages = cached
+            .into_iter()
+            .filter_map(|(url, body)| Some((catalog_sections::section_of_url(url)?, url, body)))
+            .map(|(section, url, body)| {
+                serde_json::from_str(body)
+                    .ok()
+                    .map(|page| (section.to_string(), url.to_string(), page))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some((pages, fetched_at))
+    }
+
+    const CATALOG_PAGE_SIZE: usize = 6;
+    const MAX_CONCURRENT_PAGES: usize = 4;
+
+    /// Most pages fetched for one section, whether or not the catalog
+    /// reports how many it has.
+    const MAX_CATALOG_PAGES: usize = 50;
+
+    /// A section's pages in page order, each paired with its URL.
+    type CatalogPages = Vec<(String, CatalogResponse)>;
+
+    fn catalog_page_url(section: &str, page_number: usize) -> String {
+        format!(
+            "{}?device=web&pageSize={}&pageNumber={}",
+            catalog_sections::section_url(section),
+            CATALOG_PAGE_SIZE,
+            page_number
+        )
+    }
+
+    /// Fetches every page of a catalog section in page order, paired with
+    /// its URL. The first page says how many there are; the rest are
+    /// fetched concurrently.
+    async fn fetch_catalog_pages(
+        app: &tauri::AppHandle,
+        http: &HttpClient,
+        catalog: &CatalogState,
+        account: Option<&str>,
+        section: &str,
+    ) -> Result<Vec<(String, CatalogResponse)>, LoonieError> {
+        let first_url = catalog_page_url(section, 1);
+        let first = fetch_catalog_page(app, http, catalog, account, &first_url).await?;
+        let page_count = first.lineups.page_count(CATALOG_PAGE_SIZE);
+        let mut pages = vec![(first_url, first)];
+
+        let Some(page_count) = page_count else {
+            return fetch_remaining_pages_sequentially(app, http, catalog, account, section, pages)
+                .await;
+        };
+
+        let mut remaining = 2..=page_count;
+        let mut in_flight = tokio::task::JoinSet::new();
+        let mut fetched = Vec::new();
+
+        loop {
+            while in_flight.len() < MAX_CONCURRENT_PAGES {
+                let Some(page_number) = remaining.next() else {
+                    break;
+                };
+                let app = app.clone();
+                let catalog = catalog.clone();
+                let account = account.map(|a| a.to_string());
+                let url = catalog_page_url(section, page_number);
+                in_flight.spawn(async move {
+                    let http = app.state::<HttpClient>();
+                    let page =
+                        fetch_catalog_page(&app, &http, &catalog, account.as_deref(), &url).await;
+                    (page_number, url, page)
+                });
             }
 
-            page_number += 1;
+            let Some(joined) = in_flight.join_next().await else {
+                break;
+            };
+            let (page_number, url, page) = joined.map_err(|e| LoonieError::Internal {
+                message: format!("Catalog page task failed: {}", e),
+            })?;
+            fetched.push((page_number, url, page?));
+        }
+
+        fetched.sort_by_key(|(page_number, _, _)| *page_number);
+        pages.extend(fetched.into_iter().map(|(_, url, page)| (url, page)));
+        Ok(pages)
+    }
 
-            // Safety limit - don't fetch more than 10 pages
-            if page_number > 10 {
+    /// Follows pages one at a time until a short one, for a catalog without
+    /// page metadata.
+    async fn fetch_remaining_pages_sequentially(
+        app: &tauri::AppHandle,
+        http: &HttpClient,
+        catalog: &CatalogState,
+        account: Option<&str>,
+        section: &str,
+        mut pages: Vec<(String, CatalogResponse)>,
+    ) -> Result<Vec<(String, CatalogResponse)>, LoonieError> {
+        while pages.len() < MAX_CATALOG_PAGES {
+            let is_last_page = pages
+                .last()
+                .is_some_and(|(_, page)| page.lineups.results.len() < CATALOG_PAGE_SIZE);
+            if is_last_page {
                 break;
             }
+
+            let url = catalog_page_url(section, pages.len() + 1);
+            let page = fetch_catalog_page(app, http, catalog, account, &url).await?;
+            pages.push((url, page));
         }
+        Ok(pages)
+    }
 
-        Ok(all_streams)
+    /// Fetches one catalog page, revalidating the cached copy if we have one.
+    async fn fetch_catalog_page(
+        app: &tauri::AppHandle,
+        http: &HttpClient,
+        catalog: &CatalogState,
+        account: Option<&str>,
+        url: &str,
+    ) -> Result<CatalogResponse, LoonieError> {
+        let conditional = catalog.cache.lock().unwrap().conditional_headers(url);
+        let request = http
+            .client()
+            .get(url)
+            .header("Accept", "application/json")
+            .headers(conditional);
+
+        let response = http.send_as(app, account, request).await?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            let cached = catalog
+                .cache
+                .lock()
+                .unwrap()
+                .page(url)
+                .map(|page| page.body.clone());
+            let body = cached.ok_or(LoonieError::CatalogUnavailable {
+                status: Some(response.status().as_u16()),
+            })?;
+            return serde_json::from_str(&body)
+                .map_err(|e| LoonieError::parse("cached catalog response", e));
+        }
+
+        if !response.status().is_success() {
+            return Err(LoonieError::CatalogUnavailable {
+                status: Some(response.status().as_u16()),
+            });
+        }
+
+        let headers = response.headers().clone();
+        let body = response.text().await?;
+        let page =
+            serde_json::from_str(&body).map_err(|e| LoonieError::parse("catalog response", e))?;
+        catalog.cache.lock().unwrap().store(url, &headers, body);
+        Ok(page)
+    }
+
+    /// Streams of `(section, page)` pairs. A stream listed in several
+    /// sections keeps the first.
+    fn streams_from_pages(
+        pages: &[(String, CatalogResponse)],
+        now: DateTime<Utc>,
+    ) -> Vec<StreamInfo> {
+        let mut seen_ids: HashSet<String> = HashSet::new();
+        pages
+            .iter()
+            .flat_map(|(section, page)| {
+                convert_lineups_to_streams(&page.lineups.results, section, &mut seen_ids, now)
+            })
+            .collect()
     }
 
     fn convert_lineups_to_streams(
         lineups: &[Lineup],
+        section: &str,
         seen_ids: &mut HashSet<String>,
+        now: DateTime<Utc>,
     ) -> Vec<StreamInfo> {
         let mut streams = Vec::new();
 
@@ -358,30 +1076,25 @@ mod commands {
                     .or(item.air_date_alt.clone())
                     .unwrap_or_default();
 
-                let is_past = DateTime::parse_from_rfc3339(&air_date)
-                    .map(|dt| dt.with_timezone(&Utc) < Utc::now())
-                    .unwrap_or(false);
-
-                let status = if !item.is_vod_enabled && is_past {
-                    "live"
-                } else if item.is_vod_enabled {
-                    "replay"
-                } else {
-                    "upcoming"
-                };
+                let start = parse_catalog_date(&air_date);
+                let end = start.map(|start| item_end_time(item, start, &lineup.title));
+                let status =
+                    StreamStatus::at(&item.item_type, start, end, item.is_vod_enabled, now);
 
                 let stream = StreamInfo {
                     id: stream_id,
                     title: item.title.clone(),
                     description: item.description.clone(),
                     sport: lineup.title.clone(),
-                    status: status.to_string(),
+                    status,
                     start_time: air_date,
-                    end_time: None,
+                    end_time: end.map(|end| end.to_rfc3339_opts(SecondsFormat::Secs, true)),
                     thumbnail_url,
                     stream_url: item.url.clone(),
                     requires_auth: item.tier == "Member" || item.tier == "Premium",
                     is_premium: item.tier == "Premium",
+                    vod_enabled: item.is_vod_enabled,
+                    section: section.to_string(),
                 };
 
                 streams.push(stream);
@@ -401,10 +1114,17 @@ mod commands {
 
     #[derive(Debug, Serialize, Deserialize)]
     pub struct StreamManifest {
+        /// What the player loads: the stream through the local proxy, or
+        /// `source_url` itself if the proxy isn't running.
         pub url: String,
+        /// The master playlist on the CDN.
+        pub source_url: String,
         pub error_code: i32,
         pub message: Option<String>,
         pub bitrates: Vec<BitrateInfo>,
+        /// The master playlist behind `url`, if it could be fetched.
+        #[serde(default)]
+        pub playlist: Option<MasterPlaylist>,
     }
 
     #[derive(Debug, Deserialize)]
@@ -425,11 +1145,95 @@ mod commands {
         lines: String,
     }
 
+    /// Fetches the master playlist the validation API pointed at. It lives on
+    /// the CDN, which needs no CBC cookies, so none are sent.
+    async fn fetch_master_playlist(
+        app: &tauri::AppHandle,
+        http: &HttpClient,
+        url: &str,
+    ) -> Result<MasterPlaylist, LoonieError> {
+        let url = reqwest::Url::parse(url).map_err(|e| LoonieError::parse("playlist URL", e))?;
+        let request = http.client().get(url);
+        let response = http.send_as(app, None, request).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            return Err(LoonieError::Network {
+                message: format!("Playlist request returned status: {}", status),
+                status: Some(status.as_u16()),
+            });
+        }
+
+        // Variants are relative to where a redirect left off
+        let base = response.url().clone();
+        let body = response.text().await?;
+        hls::parse_master(&body, &base)
+    }
+
+    /// Stops proxying a stream, by the URL `get_stream_manifest` gave its
+    /// player.
+    #[tauri::command]
+    pub fn close_stream(app: tauri::AppHandle, url: String) {
+        if let Some(proxy) = app.try_state::<HlsProxy>() {
+            proxy.close(&url);
+        }
+    }
+
+    /// Bytes each viewport's player has loaded through the stream proxy.
+    #[tauri::command]
+    pub fn get_stream_traffic(app: tauri::AppHandle) -> Vec<ViewportTraffic> {
+        app.try_state::<HlsProxy>()
+            .map(|proxy| proxy.traffic())
+            .unwrap_or_default()
+    }
+
+    #[tauri::command]
+    pub fn get_quality_settings(quality: State<'_, QualityState>) -> QualitySettings {
+        quality.settings()
+    }
+
+    /// Caps what a viewport plays. A cap without limits removes it.
+    #[tauri::command]
+    pub fn set_viewport_quality(
+        app: tauri::AppHandle,
+        quality: State<'_, QualityState>,
+        viewport_index: u32,
+        cap: QualityCap,
+    ) -> Result<QualitySettings, LoonieError> {
+        quality.update(&app, |settings| {
+            if cap.is_unlimited() {
+                settings.caps.remove(&viewport_index);
+            } else {
+                settings.caps.insert(viewport_index, cap);
+            }
+        })
+    }
+
+    /// Bits per second split across the viewports playing at once, or
+    /// `None` for no budget.
+    #[tauri::command]
+    pub fn set_bandwidth_budget(
+        app: tauri::AppHandle,
+        quality: State<'_, QualityState>,
+        bits_per_second: Option<u64>,
+    ) -> Result<QualitySettings, LoonieError> {
+        quality.update(&app, |settings| settings.bandwidth_budget = bits_per_second)
+    }
+
+    /// The viewport whose audio is on gets the largest share of the budget.
+    #[tauri::command]
+    pub fn set_audio_viewport(quality: State<'_, QualityState>, viewport_index: Option<u32>) {
+        quality.set_audio_viewport(viewport_index);
+    }
+
     #[tauri::command]
     pub async fn get_stream_manifest(
+        app: tauri::AppHandle,
         stream_url: String,
+        viewport_index: Option<u32>,
         state: State<'_, AuthState>,
-    ) -> Result<StreamManifest, String> {
+        http: State<'_, HttpClient>,
+    ) -> Result<StreamManifest, LoonieError> {
         println!(
             "[get_stream_manifest] Called with stream_url: {}",
             stream_url
@@ -437,71 +1241,33 @@ mod commands {
 
         // Extract idMedia from the stream_url
         // The URL ends with something like "...-30093" where 30093 is the idMedia
-        let id_media = stream_url
-            .split('-')
-            .last()
-            .and_then(|s| s.parse::<i64>().ok())
-            .ok_or("Invalid stream URL format")?;
+        let id_media =
+            validation::media_id(&stream_url).ok_or_else(|| LoonieError::InvalidStreamUrl {
+                url: stream_url.clone(),
+            })?;
 
         println!("[get_stream_manifest] Extracted idMedia: {}", id_media);
 
-        // Check if user is authenticated and extract cookies
-        // Clone cookies here to release the mutex lock before any await points
-        let cookies = {
-            let session = state.session.lock().unwrap();
-            let has_session = session.is_some();
-            println!("[get_stream_manifest] Session exists: {}", has_session);
-            if has_session {
-                println!(
-                    "[get_stream_manifest] Cookie count: {}",
-                    session.as_ref().unwrap().cookies.len()
-                );
-                for (key, _value) in session.as_ref().unwrap().cookies.iter() {
-                    println!("[get_stream_manifest] Cookie key: {}", key);
-                }
-            }
-            session.as_ref().map(|s| s.cookies.clone())
-        };
-
-        let session_cookies = cookies.ok_or("Not authenticated")?;
-        println!("[get_stream_manifest] Successfully extracted session cookies");
+        let account = state
+            .account_for_viewport(viewport_index)
+            .ok_or(LoonieError::NotAuthenticated)?;
 
         // Build the validation URL
-        const VALIDATION_BASE_URL: &str = "https://services.radio-canada.ca/media/validation/v2/";
-        let validation_url = format!(
-            "{}?appCode=medianetlive&connectionType=hd&deviceType=ipad&idMedia={}&multibitrate=true&output=json&tech=hls&manifestVersion=2&manifestType=desktop",
-            VALIDATION_BASE_URL, id_media
-        );
+        let validation_url = validation::validation_url(id_media);
         println!("[get_stream_manifest] Validation URL: {}", validation_url);
 
-        // Create client and add authentication cookies
-        let client = reqwest::Client::new();
-        let cookie_header: String = session_cookies
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<_>>()
-            .join("; ");
-
-        println!(
-            "[get_stream_manifest] Cookie header length: {} chars",
-            cookie_header.len()
-        );
-        println!(
-            "[get_stream_manifest] Cookie header preview: {}...",
-            cookie_header.chars().take(100).collect::<String>()
-        );
-
         // Make the request
         println!("[get_stream_manifest] Sending request...");
-        let response = client
+        let request = http
+            .client()
             .get(&validation_url)
-            .header("Accept", "application/json")
-            .header("Cookie", cookie_header)
-            .send()
+            .header("Accept", "application/json");
+        let response = http
+            .send_as(&app, Some(&account), request)
             .await
             .map_err(|e| {
                 println!("[get_stream_manifest] Request failed: {}", e);
-                format!("Failed to fetch stream manifest: {}", e)
+                LoonieError::from(e)
             })?;
 
         println!(
@@ -512,26 +1278,29 @@ mod commands {
         // Handle authentication errors
         if response.status() == 401 {
             println!("[get_stream_manifest] Got 401 - session expired");
-            return Err("Session expired - please login again".to_string());
+            return Err(LoonieError::SessionExpired {
+                account: Some(account),
+            });
         }
 
         if !response.status().is_success() {
             let status = response.status();
             println!("[get_stream_manifest] Non-success status: {}", status);
-            return Err(format!("Manifest API returned status: {}", status));
+            return Err(LoonieError::Network {
+                message: format!("Manifest API returned status: {}", status),
+                status: Some(status.as_u16()),
+            });
         }
 
         // Parse the response
         let response_text = response.text().await.map_err(|e| {
             println!("[get_stream_manifest] Failed to get response text: {}", e);
-            format!("Failed to get response text: {}", e)
+            LoonieError::from(e)
         })?;
 
-        println!("[get_stream_manifest] Response body: {}", response_text);
-
         let validation: ValidationResponse = serde_json::from_str(&response_text).map_err(|e| {
             println!("[get_stream_manifest] Failed to parse JSON: {}", e);
-            format!("Failed to parse manifest response: {}", e)
+            LoonieError::parse("manifest response", e)
         })?;
 
         // Check for API-level errors
@@ -540,13 +1309,9 @@ mod commands {
                 "[get_stream_manifest] API error code: {}",
                 validation.error_code
             );
-            return Err(format!(
-                "API error {}: {}",
-                validation.error_code,
-                validation
-                    .message
-                    .unwrap_or_else(|| "Unknown error".to_string())
-            ));
+            let failure =
+                ValidationFailure::classify(validation.error_code, validation.message.as_deref());
+            return Err(failure.into_error(validation.error_code, validation.message, &account));
         }
 
         println!("[get_stream_manifest] Success! Got manifest URL");
@@ -563,11 +1328,34 @@ mod commands {
             })
             .collect();
 
+        // The player can still start from the URL alone, so a playlist that
+        // can't be read only costs the quality details
+        let playlist = match fetch_master_playlist(&app, &http, &validation.url).await {
+            Ok(playlist) => Some(playlist),
+            Err(e) => {
+                eprintln!(
+                    "[get_stream_manifest] Failed to read master playlist: {}",
+                    e
+                );
+                None
+            }
+        };
+
+        let url = match (
+            app.try_state::<HlsProxy>(),
+            reqwest::Url::parse(&validation.url),
+        ) {
+            (Some(proxy), Ok(master_url)) => proxy.open(viewport_index, master_url),
+            _ => validation.url.clone(),
+        };
+
         Ok(StreamManifest {
-            url: validation.url,
+            url,
+            source_url: validation.url,
             error_code: validation.error_code,
             message: validation.message,
             bitrates,
+            playlist,
         })
     }
 
@@ -602,6 +1390,9 @@ mod commands {
                 tier: tier.to_string(),
                 is_vod_enabled: false,
                 id_media,
+                duration: None,
+                broadcast_start: None,
+                broadcast_end: None,
             }
         }
 
@@ -612,6 +1403,7 @@ mod commands {
             fn test_stream_manifest_serialization() {
                 let manifest = StreamManifest {
                     url: "https://cbc.ca/stream.m3u8".to_string(),
+                    source_url: "https://cbc.ca/stream.m3u8".to_string(),
                     error_code: 0,
                     message: None,
                     bitrates: vec![BitrateInfo {
@@ -620,145 +1412,363 @@ mod commands {
                         height: 1080,
                         lines: "1080p".to_string(),
                     }],
+                    playlist: None,
                 };
 
                 let json = serde_json::to_string(&manifest).unwrap();
                 let deserialized: StreamManifest = serde_json::from_str(&json).unwrap();
 
-                assert_eq!(deserialized.url, "https://cbc.ca/stream.m3u8");
-                assert_eq!(deserialized.error_code, 0);
-                assert_eq!(deserialized.bitrates.len(), 1);
-                assert_eq!(deserialized.bitrates[0].bitrate, 5000000);
+                assert_eq!(deserialized.url, "https://cbc.ca/stream.m3u8");
+                assert_eq!(deserialized.error_code, 0);
+                assert_eq!(deserialized.bitrates.len(), 1);
+                assert_eq!(deserialized.bitrates[0].bitrate, 5000000);
+            }
+
+            #[test]
+            fn test_catalog_response_deserialization() {
+                let json = r#"
+                {
+                    "lineups": {
+                        "results": [
+                            {
+                                "title": "Hockey",
+                                "items": [
+                                    {
+                                        "title": "Game 1",
+                                        "key": "game1",
+                                        "url": "https://cbc.ca/game1",
+                                        "type": "Live",
+                                        "tier": "Free",
+                                        "isVodEnabled": false,
+                                        "idMedia": 123
+                                    }
+                                ]
+                            }
+                        ]
+                    }
+                }
+                "#;
+
+                let catalog: CatalogResponse = serde_json::from_str(json).unwrap();
+                assert_eq!(catalog.lineups.results.len(), 1);
+                assert_eq!(catalog.lineups.results[0].title, "Hockey");
+                assert_eq!(catalog.lineups.results[0].items.len(), 1);
+                assert_eq!(catalog.lineups.results[0].items[0].title, "Game 1");
+            }
+
+            #[test]
+            fn test_lineup_item_deserialization_with_defaults() {
+                let json = r#"
+                {
+                    "title": "Minimal Item",
+                    "url": "https://cbc.ca/minimal",
+                    "type": "Live"
+                }
+                "#;
+
+                let item: LineupItem = serde_json::from_str(json).unwrap();
+                assert_eq!(item.title, "Minimal Item");
+                assert_eq!(item.key, "");
+                assert_eq!(item.description, "");
+                assert_eq!(item.tier, "");
+                assert!(!item.is_vod_enabled);
+                assert!(item.id_media.is_none());
+                assert!(item.air_date.is_none());
+                assert!(item.air_date_alt.is_none());
+            }
+
+            #[test]
+            fn test_images_deserialization() {
+                let json = r#"
+                {
+                    "card": {
+                        "url": "https://cbc.ca/card.jpg"
+                    },
+                    "background": {
+                        "url": "https://cbc.ca/bg.jpg"
+                    }
+                }
+                "#;
+
+                let images: Images = serde_json::from_str(json).unwrap();
+                assert_eq!(images.card.unwrap().url, "https://cbc.ca/card.jpg");
+                assert_eq!(images.background.unwrap().url, "https://cbc.ca/bg.jpg");
+            }
+
+            #[test]
+            fn test_images_with_null_fields() {
+                let json = r#"
+                {
+                    "card": null,
+                    "background": {
+                        "url": "https://cbc.ca/bg.jpg"
+                    }
+                }
+                "#;
+
+                let images: Images = serde_json::from_str(json).unwrap();
+                assert!(images.card.is_none());
+                assert!(images.background.is_some());
+            }
+
+            #[test]
+            fn test_validation_response_deserialization() {
+                let json = r#"
+                {
+                    "url": "https://cbc.ca/stream.m3u8",
+                    "errorCode": 0,
+                    "message": null,
+                    "bitrates": [
+                        {
+                            "bitrate": 5000000,
+                            "width": 1920,
+                            "height": 1080,
+                            "lines": "1080p"
+                        }
+                    ]
+                }
+                "#;
+
+                let validation: ValidationResponse = serde_json::from_str(json).unwrap();
+                assert_eq!(validation.url, "https://cbc.ca/stream.m3u8");
+                assert_eq!(validation.error_code, 0);
+                assert!(validation.message.is_none());
+                assert_eq!(validation.bitrates.len(), 1);
+                assert_eq!(validation.bitrates[0].bitrate, 5000000);
+            }
+
+            #[test]
+            fn test_validation_response_with_error() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 403,
+                    "message": "Access denied",
+                    "bitrates": []
+                }
+                "#;
+
+                let validation: ValidationResponse = serde_json::from_str(json).unwrap();
+                assert_eq!(validation.url, "");
+                assert_eq!(validation.error_code, 403);
+                assert_eq!(validation.message, Some("Access denied".to_string()));
+            }
+
+            fn classify_fixture(json: &str) -> ValidationFailure {
+                let validation: ValidationResponse = serde_json::from_str(json).unwrap();
+                ValidationFailure::classify(validation.error_code, validation.message.as_deref())
+            }
+
+            #[test]
+            fn test_validation_error_geo_blocked() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 1,
+                    "message": "Ce contenu n'est pas disponible dans votre région.",
+                    "bitrates": []
+                }
+                "#;
+
+                let failure = classify_fixture(json);
+                assert_eq!(failure, ValidationFailure::GeoBlocked);
+                assert!(!failure.hint().retry);
+            }
+
+            #[test]
+            fn test_validation_error_login_required() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 35,
+                    "message": "Authentication required",
+                    "bitrates": []
+                }
+                "#;
+
+                let failure = classify_fixture(json);
+                assert_eq!(failure, ValidationFailure::LoginRequired);
+                assert!(failure.hint().relogin);
+            }
+
+            #[test]
+            fn test_validation_error_access_denied_needs_upgrade() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 403,
+                    "message": "Access denied",
+                    "bitrates": []
+                }
+                "#;
+
+                let failure = classify_fixture(json);
+                assert_eq!(failure, ValidationFailure::NotEntitled);
+                assert!(failure.hint().upgrade);
+            }
+
+            #[test]
+            fn test_validation_error_premium_in_french() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 7,
+                    "message": "Ce contenu est réservé aux abonnés Premium."
+                }
+                "#;
+
+                assert_eq!(classify_fixture(json), ValidationFailure::NotEntitled);
+            }
+
+            #[test]
+            fn test_validation_error_concurrent_streams() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 9,
+                    "message": "Too many concurrent streams for this account"
+                }
+                "#;
+
+                let failure = classify_fixture(json);
+                assert_eq!(failure, ValidationFailure::ConcurrentStreamLimit);
+                assert!(failure.hint().retry);
+            }
+
+            #[test]
+            fn test_validation_error_not_yet_available() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 12,
+                    "message": "Ce contenu n'est pas encore disponible."
+                }
+                "#;
+
+                assert_eq!(classify_fixture(json), ValidationFailure::NotYetAvailable);
+            }
+
+            #[test]
+            fn test_validation_error_expired() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 14,
+                    "message": "This content has expired"
+                }
+                "#;
+
+                let failure = classify_fixture(json);
+                assert_eq!(failure, ValidationFailure::Expired);
+                assert!(!failure.hint().retry);
+            }
+
+            #[test]
+            fn test_validation_error_connection_is_not_a_login() {
+                let json = r#"
+                {
+                    "url": "",
+                    "errorCode": 500,
+                    "message": "Connection to the media server failed"
+                }
+                "#;
+
+                let failure = classify_fixture(json);
+                assert_eq!(failure, ValidationFailure::Unknown);
+                assert!(!failure.hint().relogin);
             }
 
             #[test]
-            fn test_catalog_response_deserialization() {
+            fn test_validation_error_login_required_in_french() {
                 let json = r#"
                 {
-                    "lineups": {
-                        "results": [
-                            {
-                                "title": "Hockey",
-                                "items": [
-                                    {
-                                        "title": "Game 1",
-                                        "key": "game1",
-                                        "url": "https://cbc.ca/game1",
-                                        "type": "Live",
-                                        "tier": "Free",
-                                        "isVodEnabled": false,
-                                        "idMedia": 123
-                                    }
-                                ]
-                            }
-                        ]
-                    }
+                    "url": "",
+                    "errorCode": 40,
+                    "message": "Vous devez être connecté pour regarder ce contenu."
                 }
                 "#;
 
-                let catalog: CatalogResponse = serde_json::from_str(json).unwrap();
-                assert_eq!(catalog.lineups.results.len(), 1);
-                assert_eq!(catalog.lineups.results[0].title, "Hockey");
-                assert_eq!(catalog.lineups.results[0].items.len(), 1);
-                assert_eq!(catalog.lineups.results[0].items[0].title, "Game 1");
+                assert_eq!(classify_fixture(json), ValidationFailure::LoginRequired);
             }
 
             #[test]
-            fn test_lineup_item_deserialization_with_defaults() {
+            fn test_validation_error_session_expired() {
                 let json = r#"
                 {
-                    "title": "Minimal Item",
-                    "url": "https://cbc.ca/minimal",
-                    "type": "Live"
+                    "url": "",
+                    "errorCode": 14,
+                    "message": "Your session has expired"
                 }
                 "#;
 
-                let item: LineupItem = serde_json::from_str(json).unwrap();
-                assert_eq!(item.title, "Minimal Item");
-                assert_eq!(item.key, "");
-                assert_eq!(item.description, "");
-                assert_eq!(item.tier, "");
-                assert!(!item.is_vod_enabled);
-                assert!(item.id_media.is_none());
-                assert!(item.air_date.is_none());
-                assert!(item.air_date_alt.is_none());
+                let failure = classify_fixture(json);
+                assert_eq!(failure, ValidationFailure::LoginRequired);
+                assert!(failure.hint().relogin);
             }
 
             #[test]
-            fn test_images_deserialization() {
+            fn test_validation_error_session_expired_in_french() {
                 let json = r#"
                 {
-                    "card": {
-                        "url": "https://cbc.ca/card.jpg"
-                    },
-                    "background": {
-                        "url": "https://cbc.ca/bg.jpg"
-                    }
+                    "url": "",
+                    "errorCode": 14,
+                    "message": "Votre session a expiré. Veuillez vous reconnecter."
                 }
                 "#;
 
-                let images: Images = serde_json::from_str(json).unwrap();
-                assert_eq!(images.card.unwrap().url, "https://cbc.ca/card.jpg");
-                assert_eq!(images.background.unwrap().url, "https://cbc.ca/bg.jpg");
+                assert_eq!(classify_fixture(json), ValidationFailure::LoginRequired);
             }
 
             #[test]
-            fn test_images_with_null_fields() {
+            fn test_validation_error_session_no_longer_valid() {
                 let json = r#"
                 {
-                    "card": null,
-                    "background": {
-                        "url": "https://cbc.ca/bg.jpg"
-                    }
+                    "url": "",
+                    "errorCode": 14,
+                    "message": "Session no longer valid"
                 }
                 "#;
 
-                let images: Images = serde_json::from_str(json).unwrap();
-                assert!(images.card.is_none());
-                assert!(images.background.is_some());
+                assert_eq!(classify_fixture(json), ValidationFailure::LoginRequired);
             }
 
             #[test]
-            fn test_validation_response_deserialization() {
+            fn test_validation_error_content_no_longer_available() {
                 let json = r#"
                 {
-                    "url": "https://cbc.ca/stream.m3u8",
-                    "errorCode": 0,
-                    "message": null,
-                    "bitrates": [
-                        {
-                            "bitrate": 5000000,
-                            "width": 1920,
-                            "height": 1080,
-                            "lines": "1080p"
-                        }
-                    ]
+                    "url": "",
+                    "errorCode": 14,
+                    "message": "Ce contenu n'est plus disponible."
                 }
                 "#;
 
-                let validation: ValidationResponse = serde_json::from_str(json).unwrap();
-                assert_eq!(validation.url, "https://cbc.ca/stream.m3u8");
-                assert_eq!(validation.error_code, 0);
-                assert!(validation.message.is_none());
-                assert_eq!(validation.bitrates.len(), 1);
-                assert_eq!(validation.bitrates[0].bitrate, 5000000);
+                assert_eq!(classify_fixture(json), ValidationFailure::Expired);
             }
 
             #[test]
-            fn test_validation_response_with_error() {
+            fn test_validation_error_maps_to_command_error() {
                 let json = r#"
                 {
                     "url": "",
-                    "errorCode": 403,
-                    "message": "Access denied",
-                    "bitrates": []
+                    "errorCode": 1,
+                    "message": "Geo-restricted"
                 }
                 "#;
 
                 let validation: ValidationResponse = serde_json::from_str(json).unwrap();
-                assert_eq!(validation.url, "");
-                assert_eq!(validation.error_code, 403);
-                assert_eq!(validation.message, Some("Access denied".to_string()));
+                let error = ValidationFailure::classify(validation.error_code, None).into_error(
+                    validation.error_code,
+                    validation.message,
+                    "Home",
+                );
+                let value = serde_json::to_value(&error).unwrap();
+
+                assert_eq!(value["code"], "GEO_BLOCKED");
+                assert_eq!(value["detail"], "Geo-restricted");
+                assert_eq!(
+                    value["explanation"]["fr"],
+                    "Cette diffusion est offerte seulement au Canada."
+                );
             }
 
             #[test]
@@ -807,6 +1817,28 @@ mod commands {
                 assert_eq!(item.id_media, Some(12345));
             }
 
+            #[test]
+            fn test_duration_and_broadcast_window() {
+                let json = r#"
+                {
+                    "title": "Game",
+                    "url": "https://cbc.ca/game",
+                    "type": "Live",
+                    "duration": 5400,
+                    "broadcastStartDate": "2024-07-26T13:45:00Z",
+                    "broadcastEndDate": "2024-07-26T16:15:00Z"
+                }
+                "#;
+
+                let item: LineupItem = serde_json::from_str(json).unwrap();
+                assert_eq!(item.duration, Some(5400));
+                assert_eq!(
+                    item.broadcast_start,
+                    Some("2024-07-26T13:45:00Z".to_string())
+                );
+                assert_eq!(item.broadcast_end, Some("2024-07-26T16:15:00Z".to_string()));
+            }
+
             #[test]
             fn test_bitrate_info_roundtrip() {
                 let bitrate = BitrateInfo {
@@ -829,6 +1861,7 @@ mod commands {
             fn test_stream_manifest_with_multiple_bitrates() {
                 let manifest = StreamManifest {
                     url: "https://cbc.ca/stream.m3u8".to_string(),
+                    source_url: "https://cbc.ca/stream.m3u8".to_string(),
                     error_code: 0,
                     message: Some("Success".to_string()),
                     bitrates: vec![
@@ -845,6 +1878,7 @@ mod commands {
                             lines: "1080p".to_string(),
                         },
                     ],
+                    playlist: None,
                 };
 
                 let json = serde_json::to_string(&manifest).unwrap();
@@ -867,6 +1901,55 @@ mod commands {
 
                 let catalog: CatalogResponse = serde_json::from_str(json).unwrap();
                 assert!(catalog.lineups.results.is_empty());
+                assert_eq!(catalog.lineups.page_count(6), None);
+            }
+
+            #[test]
+            fn test_lineups_page_count_from_total_pages() {
+                let json = r#"
+                {
+                    "lineups": {
+                        "results": [],
+                        "totalPages": 14,
+                        "totalCount": 80
+                    }
+                }
+                "#;
+
+                let catalog: CatalogResponse = serde_json::from_str(json).unwrap();
+                assert_eq!(catalog.lineups.page_count(6), Some(14));
+            }
+
+            #[test]
+            fn test_lineups_page_count_from_total_count() {
+                let json = r#"
+                {
+                    "lineups": {
+                        "results": [],
+                        "totalItems": 80
+                    }
+                }
+                "#;
+
+                let catalog: CatalogResponse = serde_json::from_str(json).unwrap();
+                assert_eq!(catalog.lineups.page_count(6), Some(14));
+                assert_eq!(catalog.lineups.page_count(8), Some(10));
+            }
+
+            #[test]
+            fn test_lineups_page_count_is_capped() {
+                let json = r#"
+                {
+                    "lineups": {
+                        "results": [],
+                        "totalPages": 4000000000,
+                        "totalCount": 18446744073709551615
+                    }
+                }
+                "#;
+
+                let catalog: CatalogResponse = serde_json::from_str(json).unwrap();
+                assert_eq!(catalog.lineups.page_count(6), Some(MAX_CATALOG_PAGES));
             }
         }
 
@@ -886,7 +1969,8 @@ mod commands {
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams.len(), 2);
                 assert!(streams.iter().any(|s| s.title == "Live Game"));
@@ -905,7 +1989,8 @@ mod commands {
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams.len(), 1);
                 assert_eq!(streams[0].title, "Game 1");
@@ -925,7 +2010,8 @@ mod commands {
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams.len(), 1);
                 assert_eq!(streams[0].id, "12345");
@@ -947,11 +2033,15 @@ mod commands {
                         tier: "Free".to_string(),
                         is_vod_enabled: false,
                         id_media: None,
+                        duration: None,
+                        broadcast_start: None,
+                        broadcast_end: None,
                     }],
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams.len(), 1);
                 assert_eq!(streams[0].id, "fallback-key");
@@ -969,7 +2059,8 @@ mod commands {
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 let free_stream = streams.iter().find(|s| s.id == "1").unwrap();
                 let member_stream = streams.iter().find(|s| s.id == "2").unwrap();
@@ -1008,11 +2099,15 @@ mod commands {
                         tier: "Free".to_string(),
                         is_vod_enabled: false,
                         id_media: Some(1),
+                        duration: None,
+                        broadcast_start: None,
+                        broadcast_end: None,
                     }],
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams[0].thumbnail_url, "https://cbc.ca/card.jpg");
             }
@@ -1038,11 +2133,15 @@ mod commands {
                         tier: "Free".to_string(),
                         is_vod_enabled: false,
                         id_media: Some(1),
+                        duration: None,
+                        broadcast_start: None,
+                        broadcast_end: None,
                     }],
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams[0].thumbnail_url, "https://cbc.ca/bg.jpg");
             }
@@ -1063,11 +2162,15 @@ mod commands {
                         tier: "Free".to_string(),
                         is_vod_enabled: false,
                         id_media: Some(1),
+                        duration: None,
+                        broadcast_start: None,
+                        broadcast_end: None,
                     }],
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams[0].thumbnail_url, "");
             }
@@ -1088,11 +2191,15 @@ mod commands {
                         tier: "Free".to_string(),
                         is_vod_enabled: false,
                         id_media: Some(1),
+                        duration: None,
+                        broadcast_start: None,
+                        broadcast_end: None,
                     }],
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams[0].start_time, "2024-07-26T16:00:00Z");
             }
@@ -1111,7 +2218,8 @@ mod commands {
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams[0].sport, "Figure Skating");
             }
@@ -1132,11 +2240,15 @@ mod commands {
                         tier: "Free".to_string(),
                         is_vod_enabled: false,
                         id_media: Some(1),
+                        duration: None,
+                        broadcast_start: None,
+                        broadcast_end: None,
                     }],
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(
                     streams[0].stream_url,
@@ -1170,7 +2282,8 @@ mod commands {
                 ];
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&lineups, &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&lineups, "olympics", &mut seen_ids, Utc::now());
 
                 assert_eq!(streams.len(), 2);
                 assert!(streams.iter().any(|s| s.sport == "Hockey"));
@@ -1181,7 +2294,8 @@ mod commands {
             fn test_convert_lineups_to_streams_empty_lineups() {
                 let lineups: Vec<Lineup> = vec![];
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&lineups, &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&lineups, "olympics", &mut seen_ids, Utc::now());
 
                 assert!(streams.is_empty());
             }
@@ -1194,7 +2308,8 @@ mod commands {
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert!(streams.is_empty());
             }
@@ -1210,30 +2325,227 @@ mod commands {
                 };
 
                 let mut seen_ids = HashSet::new();
-                let streams = convert_lineups_to_streams(&[lineup], &mut seen_ids);
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut seen_ids, Utc::now());
 
                 assert!(streams.is_empty());
             }
+
+            #[test]
+            fn test_streams_from_pages_keeps_page_order_and_deduplicates() {
+                let page = |section: &str, sport: &str, items: Vec<LineupItem>| {
+                    let page = CatalogResponse {
+                        lineups: Lineups {
+                            results: vec![Lineup {
+                                title: sport.to_string(),
+                                items,
+                            }],
+                            total_pages: Some(2),
+                            total_count: None,
+                        },
+                    };
+                    (section.to_string(), page)
+                };
+                let pages = vec![
+                    page(
+                        "olympics",
+                        "Hockey",
+                        vec![
+                            create_test_lineup_item("Game 1", "Live", "Free", Some(1), "game1"),
+                            create_test_lineup_item("Game 2", "Live", "Free", Some(2), "game2"),
+                        ],
+                    ),
+                    page(
+                        "paralympics",
+                        "Curling",
+                        vec![
+                            create_test_lineup_item("Game 2 Again", "Live", "Free", Some(2), "again"),
+                            create_test_lineup_item("Draw 1", "Live", "Free", Some(3), "draw1"),
+                        ],
+                    ),
+                ];
+
+                let streams = streams_from_pages(&pages, Utc::now());
+                let ids: Vec<_> = streams.iter().map(|s| s.id.as_str()).collect();
+
+                assert_eq!(ids, vec!["1", "2", "3"]);
+                assert_eq!(streams[1].title, "Game 2");
+                let sections: Vec<_> = streams.iter().map(|s| s.section.as_str()).collect();
+                assert_eq!(sections, vec!["olympics", "olympics", "paralympics"]);
+            }
+
+            fn end_time_of(item: LineupItem, sport: &str) -> Option<String> {
+                let lineup = Lineup {
+                    title: sport.to_string(),
+                    items: vec![item],
+                };
+                convert_lineups_to_streams(&[lineup], "olympics", &mut HashSet::new(), Utc::now())
+                    .remove(0)
+                    .end_time
+            }
+
+            #[test]
+            fn test_convert_lineups_to_streams_end_time_from_broadcast_window() {
+                let mut item = create_test_lineup_item("Game", "Live", "Free", Some(1), "game");
+                item.duration = Some(3600);
+                item.broadcast_end = Some("2024-07-26T16:30:00Z".to_string());
+
+                assert_eq!(
+                    end_time_of(item, "Hockey"),
+                    Some("2024-07-26T16:30:00Z".to_string())
+                );
+            }
+
+            #[test]
+            fn test_convert_lineups_to_streams_end_time_from_duration() {
+                let mut item = create_test_lineup_item("Game", "Live", "Free", Some(1), "game");
+                item.duration = Some(5400);
+
+                assert_eq!(
+                    end_time_of(item, "Hockey"),
+                    Some("2024-07-26T15:30:00Z".to_string())
+                );
+            }
+
+            #[test]
+            fn test_convert_lineups_to_streams_end_time_falls_back_to_sport() {
+                let item = create_test_lineup_item("Run", "Live", "Free", Some(1), "run");
+                assert_eq!(
+                    end_time_of(item, "Biathlon"),
+                    Some("2024-07-26T15:30:00Z".to_string())
+                );
+
+                let mut item = create_test_lineup_item("Game", "Live", "Free", Some(1), "game");
+                item.duration = Some(0);
+                assert_eq!(
+                    end_time_of(item, "Hockey"),
+                    Some("2024-07-26T17:00:00Z".to_string())
+                );
+            }
+
+            #[test]
+            fn test_convert_lineups_to_streams_no_end_time_without_air_date() {
+                let mut item = create_test_lineup_item("Game", "Live", "Free", Some(1), "game");
+                item.air_date = None;
+                item.duration = Some(3600);
+
+                assert_eq!(end_time_of(item, "Hockey"), None);
+            }
+
+            #[test]
+            fn test_convert_lineups_to_streams_status_uses_end_time() {
+                let mut item = create_test_lineup_item("Run", "Live", "Free", Some(1), "run");
+                item.duration = Some(1800);
+                let lineup = Lineup {
+                    title: "Biathlon".to_string(),
+                    items: vec![item],
+                };
+                let now = DateTime::parse_from_rfc3339("2024-07-26T14:45:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc);
+
+                let streams =
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut HashSet::new(), now);
+                assert_eq!(streams[0].status, StreamStatus::Ended);
+            }
+
+            #[test]
+            fn test_convert_lineups_to_streams_status_follows_clock() {
+                let statuses_at = |now: &str| {
+                    let mut replay =
+                        create_test_lineup_item("Replay", "Media", "Free", Some(2), "replay");
+                    replay.is_vod_enabled = true;
+                    let lineup = Lineup {
+                        title: "Hockey".to_string(),
+                        items: vec![
+                            create_test_lineup_item("Game", "Live", "Free", Some(1), "game"),
+                            replay,
+                        ],
+                    };
+                    let now = DateTime::parse_from_rfc3339(now)
+                        .unwrap()
+                        .with_timezone(&Utc);
+
+                    convert_lineups_to_streams(&[lineup], "olympics", &mut HashSet::new(), now)
+                        .into_iter()
+                        .map(|s| s.status)
+                        .collect::<Vec<_>>()
+                };
+
+                assert_eq!(
+                    statuses_at("2024-07-26T13:00:00Z"),
+                    vec![StreamStatus::Upcoming, StreamStatus::Upcoming]
+                );
+                assert_eq!(
+                    statuses_at("2024-07-26T15:00:00Z"),
+                    vec![StreamStatus::Live, StreamStatus::Replay]
+                );
+                assert_eq!(
+                    statuses_at("2024-07-27T09:00:00Z"),
+                    vec![StreamStatus::Ended, StreamStatus::Replay]
+                );
+            }
         }
     }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let builder = tauri::Builder::default()
+    let builder = tauri::Builder::default();
+
+    // Registered first so a second launch, such as from a calendar link,
+    // hands its link to the running app through the deep-link plugin and
+    // exits before setting anything up
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.set_focus();
+        }
+    }));
+
+    let builder = builder
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_oauth::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(AuthState::new())
+        .manage(HttpClient::new())
+        .manage(CatalogState::default())
+        .manage(CatalogWatcher::default())
+        .manage(ReminderState::default())
+        .manage(QualityState::default())
+        .manage(PendingStreamLink::default())
         .invoke_handler(tauri::generate_handler![
             commands::check_auth_status,
-            commands::set_auth_session,
             commands::logout,
+            commands::get_account_profile,
+            commands::list_accounts,
+            commands::switch_account,
+            commands::remove_account,
+            commands::pin_viewport_account,
             commands::start_cbc_auth,
             commands::cancel_cbc_auth,
-            commands::fetch_olympic_streams,
-            commands::get_stream_manifest
+            commands::import_session,
+            commands::fetch_streams,
+            commands::list_catalog_sections,
+            commands::set_catalog_sections,
+            commands::set_catalog_watch_interval,
+            commands::search_streams,
+            commands::get_schedule,
+            commands::export_calendar,
+            commands::take_stream_link,
+            commands::add_reminder,
+            commands::cancel_reminder,
+            commands::list_reminders,
+            commands::get_stream_manifest,
+            commands::close_stream,
+            commands::get_stream_traffic,
+            commands::get_quality_settings,
+            commands::set_viewport_quality,
+            commands::set_bandwidth_budget,
+            commands::set_audio_viewport
         ]);
 
     #[cfg(desktop)]
@@ -1241,6 +2553,54 @@ pub fn run() {
 
     builder
         .setup(|app| {
+            if let Some(accounts) = session_store::load(app.handle()) {
+                *app.state::<AuthState>().accounts.lock().unwrap() = accounts;
+            }
+            *app.state::<CatalogState>().cache.lock().unwrap() = catalog_cache::load(app.handle());
+            *app.state::<CatalogState>().sections.lock().unwrap() =
+                catalog_sections::load(app.handle());
+            session_monitor::spawn(app.handle().clone());
+            catalog_watcher::spawn(app.handle().clone());
+            app.state::<ReminderState>()
+                .restore(reminders::load(app.handle()));
+            reminders::spawn(app.handle().clone());
+            app.state::<QualityState>()
+                .restore(quality::load(app.handle()));
+            let quality = app.state::<QualityState>().inner().clone();
+            match tauri::async_runtime::block_on(HlsProxy::start(app.handle().clone(), quality)) {
+                Ok(proxy) => {
+                    app.manage(proxy);
+                }
+                Err(e) => eprintln!("{}", e),
+            }
+
+            // Links from exported calendar events
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                eprintln!(
+                    "Failed to register the {} scheme: {}",
+                    calendar::LINK_SCHEME,
+                    e
+                );
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    open_stream_link(&handle, url.as_str());
+                }
+            });
+            // Nothing listens for `open-stream` yet: the frontend takes this
+            // one once it has mounted
+            match app.deep_link().get_current() {
+                Ok(urls) => {
+                    let id = urls.unwrap_or_default().iter().find_map(|url| {
+                        calendar::stream_id_from_link(url.as_str()).map(|id| id.to_string())
+                    });
+                    *app.state::<PendingStreamLink>().0.lock().unwrap() = id;
+                }
+                Err(e) => eprintln!("Failed to read the link the app was opened with: {}", e),
+            }
+
             #[cfg(desktop)]
             {
                 let handle = app.handle().clone();
@@ -1256,6 +2616,45 @@ pub fn run() {
         .expect("error while running tauri application");
 }
 
+/// Brings the app forward and tells the frontend to play the stream behind
+/// a `loonievision://stream/<id>` link. A link that comes in before the
+/// catalog is loaded, or for a stream it doesn't have yet, waits for a
+/// fresh catalog.
+fn open_stream_link(app: &tauri::AppHandle, link: &str) {
+    let Some(id) = calendar::stream_id_from_link(link) else {
+        return;
+    };
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_focus();
+    }
+
+    let app = app.clone();
+    let id = id.to_string();
+    tauri::async_runtime::spawn(async move {
+        match stream_for_link(&app, &id).await {
+            Ok(Some(stream)) => {
+                let _ = app.emit("open-stream", stream);
+            }
+            Ok(None) => eprintln!("No stream {} in the catalog for its link", id),
+            Err(e) => eprintln!("Failed to load the catalog for a stream link: {}", e),
+        }
+    });
+}
+
+/// Stream `id` from the latest catalog, or from a fresh one if it isn't
+/// loaded yet or doesn't have the stream.
+async fn stream_for_link(
+    app: &tauri::AppHandle,
+    id: &str,
+) -> Result<Option<StreamInfo>, LoonieError> {
+    let find = |catalog: StreamCatalog| catalog.streams.into_iter().find(|s| s.id == id);
+    let latest = app.state::<CatalogState>().latest.lock().unwrap().clone();
+    if let Some(stream) = latest.and_then(find) {
+        return Ok(Some(stream));
+    }
+    Ok(find(commands::load_catalog(app).await?))
+}
 
 #[cfg(desktop)]
 async fn update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
@@ -1314,7 +2713,7 @@ async fn update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
                         println!("Download progress: {}%", percentage);
                         progress_app
                             .dialog()
-                            .message(&format!("Downloading update... {}%", percentage))
+                            .message(format!("Downloading update... {}%", percentage))
                             .title("Downloading Update")
                             .kind(MessageDialogKind::Info)
                             .buttons(MessageDialogButtons::OkCancel)
@@ -1353,8 +2752,8 @@ mod auth_state_tests {
     fn test_auth_state_new_creates_empty_session() {
         let auth_state = AuthState::new();
 
-        let session = auth_state.session.lock().unwrap();
-        assert!(session.is_none());
+        assert!(auth_state.accounts.lock().unwrap().is_empty());
+        assert!(auth_state.active_session().is_none());
     }
 
     #[test]
@@ -1363,22 +2762,24 @@ mod auth_state_tests {
         let auth_state_clone = auth_state.clone();
 
         let handle = std::thread::spawn(move || {
-            let mut session = auth_state_clone.session.lock().unwrap();
-            *session = Some(AuthSession {
-                cookies: HashMap::from([("session_id".to_string(), "abc123".to_string())]),
-                user_id: Some("user123".to_string()),
-                expires_at: 1234567890,
-            });
+            let mut accounts = auth_state_clone.accounts.lock().unwrap();
+            accounts.insert(
+                "Home".to_string(),
+                AuthSession {
+                    cookies: HashMap::from([("session_id".to_string(), "abc123".to_string())]),
+                    cookie_meta: vec![],
+                    user_id: Some("user123".to_string()),
+                    profile: None,
+                    expires_at: 1234567890,
+                },
+            );
         });
 
         handle.join().unwrap();
 
-        let session = auth_state.session.lock().unwrap();
+        let session = auth_state.active_session();
         assert!(session.is_some());
-        assert_eq!(
-            session.as_ref().unwrap().user_id,
-            Some("user123".to_string())
-        );
+        assert_eq!(session.unwrap().user_id, Some("user123".to_string()));
     }
 
     #[test]
@@ -1401,7 +2802,9 @@ mod auth_state_tests {
     fn test_auth_session_default_values() {
         let session = AuthSession {
             cookies: HashMap::new(),
+            cookie_meta: vec![],
             user_id: None,
+            profile: None,
             expires_at: 0,
         };
 
@@ -1419,7 +2822,9 @@ mod auth_state_tests {
 
         let session = AuthSession {
             cookies: cookies.clone(),
+            cookie_meta: vec![],
             user_id: Some("user123".to_string()),
+            profile: None,
             expires_at: 1234567890,
         };
 
@@ -1436,7 +2841,9 @@ mod auth_state_tests {
     fn test_auth_session_serialization() {
         let session = AuthSession {
             cookies: HashMap::from([("session_id".to_string(), "abc123".to_string())]),
+            cookie_meta: vec![],
             user_id: Some("user123".to_string()),
+            profile: None,
             expires_at: 1234567890,
         };
 
@@ -1451,6 +2858,90 @@ mod auth_state_tests {
         assert_eq!(deserialized.expires_at, 1234567890);
     }
 
+    #[test]
+    fn test_auth_session_is_expired() {
+        let session = AuthSession {
+            cookies: HashMap::new(),
+            cookie_meta: vec![],
+            user_id: None,
+            profile: None,
+            expires_at: 1000,
+        };
+
+        assert!(!session.is_expired(999));
+        assert!(session.is_expired(1000));
+        assert!(session.is_expired(1001));
+    }
+
+    #[test]
+    fn test_session_summary_hides_cookies() {
+        let session = AuthSession {
+            cookies: HashMap::from([("session_id".to_string(), "abc123".to_string())]),
+            cookie_meta: vec![],
+            user_id: Some("user123".to_string()),
+            profile: Some(AccountProfile {
+                user_id: Some("user123".to_string()),
+                display_name: Some("Jane".to_string()),
+                email: None,
+                tier: SubscriptionTier::Member,
+            }),
+            expires_at: 1234567890,
+        };
+
+        let summary = SessionSummary::from_session(Some(&session));
+        let json = serde_json::to_string(&summary).unwrap();
+
+        assert!(summary.signed_in);
+        assert_eq!(summary.user_id, Some("user123".to_string()));
+        assert_eq!(summary.display_name, Some("Jane".to_string()));
+        assert_eq!(summary.expires_at, Some(1234567890));
+        assert!(!json.contains("abc123"));
+        assert!(!json.contains("session_id"));
+    }
+
+    #[test]
+    fn test_session_summary_signed_out() {
+        let summary = SessionSummary::from_session(None);
+
+        assert!(!summary.signed_in);
+        assert!(summary.user_id.is_none());
+        assert!(summary.expires_at.is_none());
+    }
+
+    #[test]
+    fn test_account_for_viewport_uses_pinned_account() {
+        let session = |user_id: &str| AuthSession {
+            cookies: HashMap::from([("sid".to_string(), user_id.to_string())]),
+            cookie_meta: vec![],
+            user_id: Some(user_id.to_string()),
+            profile: None,
+            expires_at: 1234567890,
+        };
+        let auth_state = AuthState::new();
+        {
+            let mut accounts = auth_state.accounts.lock().unwrap();
+            accounts.insert("Home".to_string(), session("home"));
+            accounts.insert("Office".to_string(), session("office"));
+        }
+        auth_state
+            .viewport_accounts
+            .lock()
+            .unwrap()
+            .insert(2, "Home".to_string());
+
+        let account_of = |viewport| auth_state.account_for_viewport(viewport);
+        assert_eq!(account_of(Some(2)), Some("Home".to_string()));
+        assert_eq!(account_of(Some(0)), Some("Office".to_string()));
+        assert_eq!(account_of(None), Some("Office".to_string()));
+
+        // A pin to an account that was removed falls back to the active one
+        auth_state.accounts.lock().unwrap().remove("Home");
+        assert_eq!(account_of(Some(2)), Some("Office".to_string()));
+
+        auth_state.unpin_account("Home");
+        assert!(auth_state.viewport_accounts.lock().unwrap().is_empty());
+    }
+
     #[test]
     fn test_stream_info_serialization() {
         let stream = StreamInfo {
@@ -1458,13 +2949,15 @@ mod auth_state_tests {
             title: "Hockey Final".to_string(),
             description: "Gold medal game".to_string(),
             sport: "Hockey".to_string(),
-            status: "live".to_string(),
+            status: StreamStatus::Live,
             start_time: "2024-07-26T14:00:00Z".to_string(),
             end_time: None,
             thumbnail_url: "https://cbc.ca/thumb.jpg".to_string(),
             stream_url: "https://gem.cbc.ca/media/123".to_string(),
             requires_auth: true,
             is_premium: false,
+            vod_enabled: false,
+            section: "olympics".to_string(),
         };
 
         let json = serde_json::to_string(&stream).unwrap();
@@ -1484,13 +2977,15 @@ mod auth_state_tests {
             title: "Game".to_string(),
             description: "Description".to_string(),
             sport: "Hockey".to_string(),
-            status: "replay".to_string(),
+            status: StreamStatus::Replay,
             start_time: "2024-07-26T14:00:00Z".to_string(),
             end_time: Some("2024-07-26T16:00:00Z".to_string()),
             thumbnail_url: "".to_string(),
             stream_url: "".to_string(),
             requires_auth: false,
             is_premium: false,
+            vod_enabled: true,
+            section: "olympics".to_string(),
         };
 
         let json = serde_json::to_string(&stream_with_end).unwrap();
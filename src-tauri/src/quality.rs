@@ -0,0 +1,349 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Runtime};
+
+use crate::hls::{Attributes, MasterPlaylist};
+use crate::{app_store, LoonieError};
+
+const STORE_FILE: &str = "settings.json";
+const QUALITY_ENTRY: &str = "quality";
+
+const STREAM_INF: &str = "#EXT-X-STREAM-INF:";
+const IFRAME_STREAM_INF: &str = "#EXT-X-I-FRAME-STREAM-INF:";
+
+/// Weight of the viewport with audio when splitting the budget; every other
+/// playing viewport weighs 1.
+const AUDIO_WEIGHT: u64 = 2;
+
+/// Highest quality a viewport may play. `None` leaves that side open.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct QualityCap {
+    pub max_height: Option<u32>,
+    /// Bits per second, against each variant's peak bandwidth.
+    pub max_bitrate: Option<u64>,
+}
+
+impl QualityCap {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_height.is_none() && self.max_bitrate.is_none()
+    }
+
+    fn allows(&self, bandwidth: u64, height: Option<u32>) -> bool {
+        let bitrate_ok = match self.max_bitrate {
+            Some(max) => bandwidth <= max,
+            None => true,
+        };
+        let height_ok = match (self.max_height, height) {
+            (Some(max), Some(height)) => height <= max,
+            _ => true,
+        };
+        bitrate_ok && height_ok
+    }
+
+    /// Whether the player may load `uri`, one of `master`'s variant or
+    /// I-frame playlists, the same way [`filter_master`] decides. URIs that
+    /// aren't listed in `master` are always allowed.
+    pub fn permits(&self, master: &MasterPlaylist, uri: &str) -> bool {
+        if self.is_unlimited() {
+            return true;
+        }
+        if let Some(iframes) = master.iframe_playlists.iter().find(|p| p.uri == uri) {
+            return self.allows(iframes.bandwidth, iframes.resolution.map(|r| r.height));
+        }
+        let Some(variant) = master.variants.iter().find(|v| v.uri == uri) else {
+            return true;
+        };
+
+        let variants: Vec<_> = master
+            .variants
+            .iter()
+            .map(|v| (v.bandwidth, v.resolution.map(|r| r.height)))
+            .collect();
+        self.allows(variant.bandwidth, variant.resolution.map(|r| r.height))
+            || Some(variant.bandwidth) == self.fallback(&variants)
+    }
+
+    /// Bandwidth of the variant that stays when the cap rules out all of
+    /// `variants`, so the stream still plays: the lowest.
+    fn fallback(&self, variants: &[(u64, Option<u32>)]) -> Option<u64> {
+        if variants.iter().any(|(b, h)| self.allows(*b, *h)) {
+            None
+        } else {
+            variants.iter().map(|(bandwidth, _)| *bandwidth).min()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct QualitySettings {
+    /// Caps by viewport index.
+    #[serde(default)]
+    pub caps: BTreeMap<u32, QualityCap>,
+    /// Bits per second for all playing viewports together.
+    #[serde(default)]
+    pub bandwidth_budget: Option<u64>,
+}
+
+impl QualitySettings {
+    /// What `viewport` may play: its own cap, lowered to its share of the
+    /// budget among the `playing` viewports.
+    pub fn limit_for(
+        &self,
+        viewport: u32,
+        playing: &[u32],
+        audio_viewport: Option<u32>,
+    ) -> QualityCap {
+        let mut limit = self.caps.get(&viewport).copied().unwrap_or_default();
+        if let Some(budget) = self.bandwidth_budget {
+            let share = budget_share(budget, viewport, playing, audio_viewport);
+            limit.max_bitrate = Some(limit.max_bitrate.map_or(share, |max| max.min(share)));
+        }
+        limit
+    }
+}
+
+/// `viewport`'s part of `budget` when it plays alongside `playing`. The
+/// viewport with audio gets a bigger part, since it's the one being watched.
+pub fn budget_share(
+    budget: u64,
+    viewport: u32,
+    playing: &[u32],
+    audio_viewport: Option<u32>,
+) -> u64 {
+    let weight = |index: u32| {
+        if Some(index) == audio_viewport {
+            AUDIO_WEIGHT
+        } else {
+            1
+        }
+    };
+    let others: u128 = playing
+        .iter()
+        .filter(|index| **index != viewport)
+        .map(|index| u128::from(weight(*index)))
+        .sum();
+    // Worked out in u128 so a huge budget can't overflow. The share is at
+    // most the budget, so it fits back in a u64.
+    let own = u128::from(weight(viewport));
+    (u128::from(budget) * own / (own + others)) as u64
+}
+
+fn variant_of(line: &str, tag: &str) -> Option<(u64, Option<u32>)> {
+    let attributes = Attributes::parse(line.strip_prefix(tag)?);
+    Some((
+        attributes.number("BANDWIDTH").unwrap_or(0),
+        attributes.resolution().map(|r| r.height),
+    ))
+}
+
+/// `playlist` without the variants `limit` rules out, so the player's ABR
+/// can't pick them. If it rules out every variant, the lowest stays so the
+/// stream still plays.
+pub fn filter_master(playlist: &str, limit: &QualityCap) -> String {
+    if limit.is_unlimited() {
+        return playlist.to_string();
+    }
+
+    let variants: Vec<_> = playlist
+        .lines()
+        .filter_map(|line| variant_of(line.trim(), STREAM_INF))
+        .collect();
+    let fallback = limit.fallback(&variants);
+
+    let mut out = String::with_capacity(playlist.len());
+    let mut skip_uri = false;
+    for line in playlist.lines() {
+        let trimmed = line.trim();
+        if let Some((bandwidth, height)) = variant_of(trimmed, STREAM_INF) {
+            skip_uri = !limit.allows(bandwidth, height) && Some(bandwidth) != fallback;
+            if skip_uri {
+                continue;
+            }
+        } else if let Some((bandwidth, height)) = variant_of(trimmed, IFRAME_STREAM_INF) {
+            if !limit.allows(bandwidth, height) {
+                continue;
+            }
+        } else if skip_uri && !trimmed.is_empty() && !trimmed.starts_with('#') {
+            skip_uri = false;
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Quality settings and the viewport with audio, managed as Tauri state
+/// and read by the stream proxy when it serves playlists.
+#[derive(Clone, Default)]
+pub struct QualityState {
+    settings: Arc<Mutex<QualitySettings>>,
+    audio_viewport: Arc<Mutex<Option<u32>>>,
+}
+
+impl QualityState {
+    pub fn settings(&self) -> QualitySettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Applies `change` and saves. Players pick the new limits up the next
+    /// time they load their master playlist.
+    pub fn update<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        change: impl FnOnce(&mut QualitySettings),
+    ) -> Result<QualitySettings, LoonieError> {
+        let mut settings = self.settings.lock().unwrap();
+        change(&mut settings);
+        save(app, &settings)?;
+        Ok(settings.clone())
+    }
+
+    pub fn restore(&self, settings: QualitySettings) {
+        *self.settings.lock().unwrap() = settings;
+    }
+
+    pub fn set_audio_viewport(&self, viewport: Option<u32>) {
+        *self.audio_viewport.lock().unwrap() = viewport;
+    }
+
+    pub fn limit_for(&self, viewport: u32, playing: &[u32]) -> QualityCap {
+        let audio_viewport = *self.audio_viewport.lock().unwrap();
+        self.settings
+            .lock()
+            .unwrap()
+            .limit_for(viewport, playing, audio_viewport)
+    }
+}
+
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> QualitySettings {
+    app_store::load(app, STORE_FILE, QUALITY_ENTRY).unwrap_or_default()
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, settings: &QualitySettings) -> Result<(), LoonieError> {
+    app_store::save(app, STORE_FILE, QUALITY_ENTRY, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hls;
+    use reqwest::Url;
+
+    const MASTER: &str = "#EXTM3U
+#EXT-X-STREAM-INF:BANDWIDTH=6000000,RESOLUTION=1920x1080
+1080p/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=3000000,RESOLUTION=1280x720
+720p/index.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=800000,RESOLUTION=640x360
+360p/index.m3u8
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=200000,RESOLUTION=1920x1080,URI=\"1080p/iframes.m3u8\"
+#EXT-X-I-FRAME-STREAM-INF:BANDWIDTH=50000,RESOLUTION=640x360,URI=\"360p/iframes.m3u8\"
+";
+
+    fn uris(playlist: &str) -> Vec<&str> {
+        playlist
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .chain(playlist.lines().filter_map(|l| l.split("URI=\"").nth(1)))
+            .map(|l| l.trim_end_matches('"'))
+            .collect()
+    }
+
+    fn cap(max_height: Option<u32>, max_bitrate: Option<u64>) -> QualityCap {
+        QualityCap {
+            max_height,
+            max_bitrate,
+        }
+    }
+
+    #[test]
+    fn test_filter_by_height() {
+        let filtered = filter_master(MASTER, &cap(Some(720), None));
+        assert_eq!(
+            uris(&filtered),
+            vec!["720p/index.m3u8", "360p/index.m3u8", "360p/iframes.m3u8"]
+        );
+    }
+
+    #[test]
+    fn test_filter_by_bitrate() {
+        let filtered = filter_master(MASTER, &cap(None, Some(1_000_000)));
+        assert_eq!(
+            uris(&filtered),
+            vec!["360p/index.m3u8", "1080p/iframes.m3u8", "360p/iframes.m3u8"]
+        );
+    }
+
+    #[test]
+    fn test_filter_keeps_lowest_variant_when_nothing_fits() {
+        let filtered = filter_master(MASTER, &cap(None, Some(100_000)));
+        assert!(uris(&filtered).contains(&"360p/index.m3u8"));
+        assert!(!uris(&filtered).contains(&"720p/index.m3u8"));
+
+        assert_eq!(filter_master(MASTER, &QualityCap::default()), MASTER);
+    }
+
+    #[test]
+    fn test_budget_favours_audio_viewport() {
+        let playing = [0, 1, 2, 3];
+        assert_eq!(budget_share(10_000_000, 1, &playing, Some(1)), 4_000_000);
+        assert_eq!(budget_share(10_000_000, 2, &playing, Some(1)), 2_000_000);
+        assert_eq!(budget_share(10_000_000, 2, &playing, None), 2_500_000);
+
+        // A viewport that just started counts even before it shows as playing
+        assert_eq!(budget_share(10_000_000, 2, &[0], Some(0)), 3_333_333);
+    }
+
+    #[test]
+    fn test_budget_share_does_not_overflow() {
+        assert_eq!(
+            budget_share(u64::MAX, 0, &[0, 1], Some(0)),
+            u64::MAX / 3 * 2
+        );
+        assert_eq!(budget_share(u64::MAX, 0, &[0], None), u64::MAX);
+    }
+
+    #[test]
+    fn test_permits_matches_the_filtered_master() {
+        let base = Url::parse("https://cdn.example.com/live/master.m3u8").unwrap();
+        let master = hls::parse_master(MASTER, &base).unwrap();
+        let uri = |path: &str| base.join(path).unwrap().to_string();
+
+        let limit = cap(Some(720), None);
+        assert!(!limit.permits(&master, &uri("1080p/index.m3u8")));
+        assert!(limit.permits(&master, &uri("720p/index.m3u8")));
+        assert!(!limit.permits(&master, &uri("1080p/iframes.m3u8")));
+        assert!(limit.permits(&master, &uri("720p/segment1.ts")));
+
+        // Nothing fits, so the lowest variant still plays
+        let limit = cap(None, Some(100_000));
+        assert!(limit.permits(&master, &uri("360p/index.m3u8")));
+        assert!(!limit.permits(&master, &uri("720p/index.m3u8")));
+    }
+
+    #[test]
+    fn test_limit_is_the_lower_of_cap_and_share() {
+        let mut settings = QualitySettings {
+            bandwidth_budget: Some(8_000_000),
+            ..Default::default()
+        };
+        settings.caps.insert(0, cap(Some(720), Some(2_000_000)));
+
+        assert_eq!(
+            settings.limit_for(0, &[0, 1], Some(0)),
+            cap(Some(720), Some(2_000_000))
+        );
+        assert_eq!(
+            settings.limit_for(1, &[0, 1], Some(0)),
+            cap(None, Some(2_666_666))
+        );
+
+        settings.bandwidth_budget = None;
+        assert_eq!(
+            settings.limit_for(1, &[0, 1], Some(0)),
+            QualityCap::default()
+        );
+    }
+}
@@ -0,0 +1,421 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Notify;
+
+use crate::{app_store, CatalogState, LoonieError, StreamInfo, StreamStatus};
+
+const STORE_FILE: &str = "reminders.json";
+const REMINDERS_ENTRY: &str = "reminders";
+
+pub const DEFAULT_LEAD_MINUTES: i64 = 10;
+
+/// Shortest lead time accepted: with none, there'd be no time left before
+/// the start to send the "starting soon" notice in.
+pub const MIN_LEAD_MINUTES: i64 = 1;
+
+/// Longest lead time accepted: a day.
+pub const MAX_LEAD_MINUTES: i64 = 1440;
+
+/// Longest the scheduler sleeps, so it picks up catalog refreshes that
+/// move a starred stream or put it on air.
+const MAX_SLEEP: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Reminder {
+    pub stream_id: String,
+    pub title: String,
+    pub sport: String,
+    /// Air date as last seen in the catalog.
+    pub start_time: String,
+    pub lead_minutes: i64,
+    /// Whether the "starting soon" notification for `start_time` went out.
+    #[serde(default)]
+    pub notified: bool,
+}
+
+impl Reminder {
+    pub fn new(stream: &StreamInfo, lead_minutes: i64) -> Self {
+        Self {
+            stream_id: stream.id.clone(),
+            title: stream.title.clone(),
+            sport: stream.sport.clone(),
+            start_time: stream.start_time.clone(),
+            lead_minutes: lead_minutes.clamp(MIN_LEAD_MINUTES, MAX_LEAD_MINUTES),
+            notified: false,
+        }
+    }
+
+    /// Lead time, clamped again for reminders saved before it was limited.
+    fn lead(&self) -> Duration {
+        Duration::minutes(self.lead_minutes.clamp(MIN_LEAD_MINUTES, MAX_LEAD_MINUTES))
+    }
+
+    fn start(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.start_time)
+            .ok()
+            .map(|start| start.with_timezone(&Utc))
+    }
+}
+
+/// A notification to show for a reminder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Notice {
+    StartingSoon(Reminder),
+    Live(Reminder),
+}
+
+impl Notice {
+    /// Title as of `now`. A notice can go out late, after the scheduler
+    /// slept through its time, so it counts the minutes actually left.
+    fn title(&self, now: DateTime<Utc>) -> String {
+        match self {
+            Self::StartingSoon(r) => {
+                let minutes_left = r.start().map_or(r.lead_minutes, |start| {
+                    // Rounded up, so it never says 0 before the start
+                    ((start - now).num_seconds().max(0) + 59) / 60
+                });
+                format!("Starting in {} min: {}", minutes_left, r.title)
+            }
+            Self::Live(r) => format!("Live now: {}", r.title),
+        }
+    }
+
+    fn body(&self) -> &str {
+        match self {
+            Self::StartingSoon(r) | Self::Live(r) => &r.sport,
+        }
+    }
+}
+
+/// Reminders keyed by stream id.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct Reminders(BTreeMap<String, Reminder>);
+
+impl Reminders {
+    /// Adds a reminder, replacing any earlier one for the same stream.
+    pub fn add(&mut self, reminder: Reminder) {
+        self.0.insert(reminder.stream_id.clone(), reminder);
+    }
+
+    pub fn cancel(&mut self, stream_id: &str) -> bool {
+        self.0.remove(stream_id).is_some()
+    }
+
+    pub fn list(&self) -> Vec<Reminder> {
+        self.0.values().cloned().collect()
+    }
+
+    /// Follows the catalog: reminders move with their air date, and are
+    /// done once their stream goes live (with a notice) or is over.
+    /// Streams missing from the catalog keep their reminder.
+    pub fn sync(&mut self, streams: &[StreamInfo]) -> Vec<Notice> {
+        let mut notices = Vec::new();
+
+        for stream in streams {
+            let Some(reminder) = self.0.get_mut(&stream.id) else {
+                continue;
+            };
+            match stream.status {
+                StreamStatus::Upcoming => {
+                    if reminder.start_time != stream.start_time {
+                        reminder.start_time = stream.start_time.clone();
+                        reminder.notified = false;
+                    }
+                    reminder.title = stream.title.clone();
+                }
+                StreamStatus::Live => {
+                    if let Some(reminder) = self.0.remove(&stream.id) {
+                        notices.push(Notice::Live(reminder));
+                    }
+                }
+                StreamStatus::Replay | StreamStatus::Ended => {
+                    self.0.remove(&stream.id);
+                }
+            }
+        }
+
+        notices
+    }
+
+    /// "Starting soon" notices due at `now`, marked as sent. Reminders whose
+    /// start has passed wait for the catalog to show the stream live.
+    pub fn due(&mut self, now: DateTime<Utc>) -> Vec<Notice> {
+        self.0
+            .values_mut()
+            .filter(|r| !r.notified)
+            .filter(|r| {
+                r.start()
+                    .is_some_and(|start| start - r.lead() <= now && now < start)
+            })
+            .map(|r| {
+                r.notified = true;
+                Notice::StartingSoon(r.clone())
+            })
+            .collect()
+    }
+
+    /// When the next "starting soon" notice falls due.
+    pub fn next_due(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.0
+            .values()
+            .filter(|r| !r.notified)
+            .filter_map(|r| Some((r.start()?, r.lead())))
+            .filter(|(start, _)| now < *start)
+            .map(|(start, lead)| start - lead)
+            .min()
+    }
+}
+
+/// Starred streams, managed as Tauri state and persisted in the store.
+#[derive(Clone, Default)]
+pub struct ReminderState {
+    reminders: Arc<Mutex<Reminders>>,
+    wake: Arc<Notify>,
+}
+
+impl ReminderState {
+    pub fn list(&self) -> Vec<Reminder> {
+        self.reminders.lock().unwrap().list()
+    }
+
+    /// Applies `change`, saves, and reschedules. Returns the reminders.
+    pub fn update<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        change: impl FnOnce(&mut Reminders),
+    ) -> Result<Vec<Reminder>, LoonieError> {
+        let mut reminders = self.reminders.lock().unwrap();
+        change(&mut reminders);
+        save(app, &reminders)?;
+        self.wake.notify_one();
+        Ok(reminders.list())
+    }
+
+    pub fn restore(&self, reminders: Reminders) {
+        *self.reminders.lock().unwrap() = reminders;
+    }
+}
+
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Reminders {
+    app_store::load(app, STORE_FILE, REMINDERS_ENTRY).unwrap_or_default()
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, reminders: &Reminders) -> Result<(), LoonieError> {
+    app_store::save(app, STORE_FILE, REMINDERS_ENTRY, reminders)
+}
+
+/// Runs in the backend so reminders fire on time even while the webview
+/// is hidden and throttled.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<ReminderState>().inner().clone();
+
+        loop {
+            let latest = app.state::<CatalogState>().latest.lock().unwrap().clone();
+            let now = Utc::now();
+            let (notices, changed, next_due) = {
+                let mut reminders = state.reminders.lock().unwrap();
+                let before = reminders.clone();
+                let mut notices = latest.map_or_else(Vec::new, |c| reminders.sync(&c.streams));
+                notices.extend(reminders.due(now));
+                (notices, *reminders != before, reminders.next_due(now))
+            };
+
+            for notice in &notices {
+                let shown = app
+                    .notification()
+                    .builder()
+                    .title(notice.title(now))
+                    .body(notice.body())
+                    .show();
+                if let Err(e) = shown {
+                    eprintln!("Failed to show reminder: {}", e);
+                }
+            }
+            if changed {
+                let list = state.list();
+                if let Err(e) = save(&app, &state.reminders.lock().unwrap()) {
+                    eprintln!("Failed to save reminders: {}", e);
+                }
+                let _ = app.emit("reminders-changed", list);
+            }
+
+            let sleep = next_due
+                .and_then(|at| (at - Utc::now()).to_std().ok())
+                .map_or(MAX_SLEEP, |until| until.min(MAX_SLEEP));
+            tokio::select! {
+                _ = tokio::time::sleep(sleep) => {}
+                _ = state.wake.notified() => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(id: &str, status: StreamStatus, start: &str) -> StreamInfo {
+        StreamInfo {
+            sport: "Curling".to_string(),
+            status,
+            start_time: start.to_string(),
+            ..StreamInfo::test(id)
+        }
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn starred(streams: &[StreamInfo]) -> Reminders {
+        let mut reminders = Reminders::default();
+        for stream in streams {
+            reminders.add(Reminder::new(stream, DEFAULT_LEAD_MINUTES));
+        }
+        reminders
+    }
+
+    #[test]
+    fn test_starting_soon_fires_once_within_the_lead_time() {
+        let upcoming = stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z");
+        let mut reminders = starred(&[upcoming]);
+
+        assert!(reminders.due(at("2026-02-11T13:49:59Z")).is_empty());
+        assert_eq!(
+            reminders.next_due(at("2026-02-11T13:00:00Z")),
+            Some(at("2026-02-11T13:50:00Z"))
+        );
+
+        let notices = reminders.due(at("2026-02-11T13:50:00Z"));
+        assert!(matches!(&notices[..], [Notice::StartingSoon(r)] if r.stream_id == "1"));
+        assert_eq!(
+            notices[0].title(at("2026-02-11T13:50:00Z")),
+            "Starting in 10 min: Stream 1"
+        );
+
+        assert!(reminders.due(at("2026-02-11T13:55:00Z")).is_empty());
+        assert_eq!(reminders.next_due(at("2026-02-11T13:55:00Z")), None);
+    }
+
+    #[test]
+    fn test_no_starting_soon_after_the_start() {
+        let upcoming = stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z");
+        let mut reminders = starred(&[upcoming]);
+
+        assert!(reminders.due(at("2026-02-11T14:05:00Z")).is_empty());
+        assert_eq!(reminders.next_due(at("2026-02-11T14:05:00Z")), None);
+    }
+
+    #[test]
+    fn test_moved_air_date_reschedules() {
+        let upcoming = stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z");
+        let mut reminders = starred(&[upcoming]);
+        reminders.due(at("2026-02-11T13:55:00Z"));
+
+        let moved = stream("1", StreamStatus::Upcoming, "2026-02-11T16:00:00Z");
+        assert!(reminders.sync(&[moved]).is_empty());
+
+        assert_eq!(reminders.list()[0].start_time, "2026-02-11T16:00:00Z");
+        assert_eq!(
+            reminders.next_due(at("2026-02-11T14:00:00Z")),
+            Some(at("2026-02-11T15:50:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_going_live_notifies_and_completes() {
+        let mut reminders = starred(&[
+            stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z"),
+            stream("2", StreamStatus::Upcoming, "2026-02-11T18:00:00Z"),
+            stream("3", StreamStatus::Upcoming, "2026-02-11T09:00:00Z"),
+        ]);
+
+        let notices = reminders.sync(&[
+            stream("1", StreamStatus::Live, "2026-02-11T14:00:00Z"),
+            stream("3", StreamStatus::Ended, "2026-02-11T09:00:00Z"),
+        ]);
+
+        assert!(matches!(&notices[..], [Notice::Live(r)] if r.stream_id == "1"));
+        let left: Vec<_> = reminders.list().into_iter().map(|r| r.stream_id).collect();
+        assert_eq!(left, vec!["2"]);
+    }
+
+    #[test]
+    fn test_lead_time_is_clamped_to_a_day() {
+        let upcoming = stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z");
+
+        assert_eq!(
+            Reminder::new(&upcoming, i64::MAX).lead_minutes,
+            MAX_LEAD_MINUTES
+        );
+        assert_eq!(Reminder::new(&upcoming, -5).lead_minutes, MIN_LEAD_MINUTES);
+
+        // Saved before the limit, a huge lead time still can't overflow
+        let mut reminders = Reminders::default();
+        reminders.add(Reminder {
+            lead_minutes: i64::MAX,
+            ..Reminder::new(&upcoming, 0)
+        });
+        assert_eq!(
+            reminders.next_due(at("2026-02-09T00:00:00Z")),
+            Some(at("2026-02-10T14:00:00Z"))
+        );
+        assert_eq!(reminders.due(at("2026-02-10T14:00:00Z")).len(), 1);
+    }
+
+    #[test]
+    fn test_late_notice_counts_the_minutes_left() {
+        let upcoming = stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z");
+        let mut reminders = starred(&[upcoming]);
+
+        let notices = reminders.due(at("2026-02-11T13:56:30Z"));
+        assert_eq!(
+            notices[0].title(at("2026-02-11T13:56:30Z")),
+            "Starting in 4 min: Stream 1"
+        );
+    }
+
+    #[test]
+    fn test_zero_lead_time_still_notifies_before_the_start() {
+        let upcoming = stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z");
+        let mut reminders = Reminders::default();
+        reminders.add(Reminder::new(&upcoming, 0));
+
+        assert_eq!(
+            reminders.next_due(at("2026-02-11T13:00:00Z")),
+            Some(at("2026-02-11T13:59:00Z"))
+        );
+        let notices = reminders.due(at("2026-02-11T13:59:00Z"));
+        assert_eq!(
+            notices[0].title(at("2026-02-11T13:59:00Z")),
+            "Starting in 1 min: Stream 1"
+        );
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut reminders = starred(&[stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z")]);
+
+        assert!(reminders.cancel("1"));
+        assert!(!reminders.cancel("1"));
+        assert!(reminders.list().is_empty());
+    }
+
+    #[test]
+    fn test_round_trips_through_json() {
+        let reminders = starred(&[stream("1", StreamStatus::Upcoming, "2026-02-11T14:00:00Z")]);
+
+        let value = serde_json::to_value(&reminders).unwrap();
+        assert!(value.get("1").is_some());
+        assert_eq!(
+            serde_json::from_value::<Reminders>(value).unwrap(),
+            reminders
+        );
+    }
+}
@@ -0,0 +1,293 @@
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{StreamInfo, StreamStatus};
+
+/// Breaks between events shorter than this are changeovers, not gaps.
+const MIN_GAP_MINUTES: i64 = 30;
+
+/// The catalog laid out by local calendar day, then by sport. Every time
+/// is RFC 3339 with the offset of `time_zone`.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Schedule {
+    pub time_zone: String,
+    pub now: String,
+    pub days: Vec<ScheduleDay>,
+    /// Streams without a start time we could read.
+    pub unscheduled: Vec<StreamInfo>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ScheduleDay {
+    pub date: NaiveDate,
+    pub is_today: bool,
+    /// Sports in the order their first event of the day starts.
+    pub sports: Vec<SportSchedule>,
+    /// Stretches between the day's events with nothing on air.
+    pub gaps: Vec<ScheduleGap>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SportSchedule {
+    pub sport: String,
+    pub events: Vec<ScheduleEvent>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ScheduleEvent {
+    pub stream: StreamInfo,
+    pub local_start: String,
+    pub local_end: Option<String>,
+    /// On air right now.
+    pub is_now: bool,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ScheduleGap {
+    pub start: String,
+    pub end: String,
+    pub minutes: i64,
+    /// Now falls inside the gap.
+    pub is_now: bool,
+}
+
+struct Placed<'a> {
+    stream: &'a StreamInfo,
+    start: DateTime<Tz>,
+    end: Option<DateTime<Tz>>,
+}
+
+fn parse_time(value: &str, tz: &Tz) -> Option<DateTime<Tz>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(tz))
+}
+
+fn local(dt: &DateTime<Tz>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+fn is_now(event: &Placed, now: &DateTime<Tz>) -> bool {
+    match &event.end {
+        Some(end) => event.start <= *now && now < end,
+        None => event.stream.status == StreamStatus::Live,
+    }
+}
+
+/// Gaps between the events of one day, sorted by start. An event without
+/// an end only covers its start.
+fn gaps(events: &[Placed], now: &DateTime<Tz>) -> Vec<ScheduleGap> {
+    let mut gaps = Vec::new();
+    let mut covered_until: Option<DateTime<Tz>> = None;
+
+    for event in events {
+        let end = event.end.unwrap_or(event.start).max(event.start);
+        if let Some(until) = covered_until {
+            let minutes = (event.start - until).num_minutes();
+            if minutes >= MIN_GAP_MINUTES {
+                gaps.push(ScheduleGap {
+                    start: local(&until),
+                    end: local(&event.start),
+                    minutes,
+                    is_now: until <= *now && *now < event.start,
+                });
+            }
+        }
+        covered_until = Some(covered_until.map_or(end, |until| until.max(end)));
+    }
+
+    gaps
+}
+
+/// Groups `streams` into the local days of `tz`. Events belong to the day
+/// they start on, even when they run past midnight.
+pub fn build_schedule(streams: &[StreamInfo], tz: Tz, now: DateTime<Utc>) -> Schedule {
+    let now = now.with_timezone(&tz);
+    let mut by_day: BTreeMap<NaiveDate, Vec<Placed>> = BTreeMap::new();
+    let mut unscheduled = Vec::new();
+
+    for stream in streams {
+        let Some(start) = parse_time(&stream.start_time, &tz) else {
+            unscheduled.push(stream.clone());
+            continue;
+        };
+        let end = stream.end_time.as_deref().and_then(|e| parse_time(e, &tz));
+        by_day
+            .entry(start.date_naive())
+            .or_default()
+            .push(Placed { stream, start, end });
+    }
+
+    let days = by_day
+        .into_iter()
+        .map(|(date, mut events)| {
+            events.sort_by_key(|e| e.start);
+
+            let mut sports: Vec<SportSchedule> = Vec::new();
+            for event in &events {
+                let scheduled = ScheduleEvent {
+                    stream: event.stream.clone(),
+                    local_start: local(&event.start),
+                    local_end: event.end.as_ref().map(local),
+                    is_now: is_now(event, &now),
+                };
+                match sports.iter_mut().find(|s| s.sport == event.stream.sport) {
+                    Some(sport) => sport.events.push(scheduled),
+                    None => sports.push(SportSchedule {
+                        sport: event.stream.sport.clone(),
+                        events: vec![scheduled],
+                    }),
+                }
+            }
+
+            ScheduleDay {
+                date,
+                is_today: date == now.date_naive(),
+                gaps: gaps(&events, &now),
+                sports,
+            }
+        })
+        .collect();
+
+    Schedule {
+        time_zone: tz.name().to_string(),
+        now: local(&now),
+        days,
+        unscheduled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(id: &str, sport: &str, start: &str, end: Option<&str>) -> StreamInfo {
+        StreamInfo {
+            sport: sport.to_string(),
+            start_time: start.to_string(),
+            end_time: end.map(str::to_string),
+            ..StreamInfo::test(id)
+        }
+    }
+
+    fn timed(id: &str, sport: &str, start: &str, minutes: i64) -> StreamInfo {
+        let end = at(start) + chrono::Duration::minutes(minutes);
+        stream(id, sport, start, Some(&end.to_rfc3339()))
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn day_ids(day: &ScheduleDay) -> Vec<(&str, Vec<&str>)> {
+        day.sports
+            .iter()
+            .map(|s| {
+                let ids = s.events.iter().map(|e| e.stream.id.as_str()).collect();
+                (s.sport.as_str(), ids)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_days_follow_the_local_time_zone() {
+        // 03:00 UTC is still the previous evening in Toronto
+        let streams = vec![
+            stream("1", "Hockey", "2026-02-11T03:00:00Z", None),
+            stream("2", "Hockey", "2026-02-11T15:00:00Z", None),
+        ];
+
+        let toronto = build_schedule(&streams, Tz::America__Toronto, at("2026-02-11T12:00:00Z"));
+        let dates: Vec<_> = toronto.days.iter().map(|d| d.date.to_string()).collect();
+        assert_eq!(dates, vec!["2026-02-10", "2026-02-11"]);
+        assert_eq!(
+            toronto.days[0].sports[0].events[0].local_start,
+            "2026-02-10T22:00:00-05:00"
+        );
+        assert_eq!(toronto.now, "2026-02-11T07:00:00-05:00");
+        assert_eq!(toronto.time_zone, "America/Toronto");
+
+        let rome = build_schedule(&streams, Tz::Europe__Rome, at("2026-02-11T12:00:00Z"));
+        assert_eq!(rome.days.len(), 1);
+        assert_eq!(
+            rome.days[0].sports[0].events[0].local_start,
+            "2026-02-11T04:00:00+01:00"
+        );
+    }
+
+    #[test]
+    fn test_sports_are_ordered_by_first_event() {
+        let streams = vec![
+            stream("curling", "Curling", "2026-02-11T14:00:00Z", None),
+            stream("hockey-2", "Hockey", "2026-02-11T16:00:00Z", None),
+            stream("hockey-1", "Hockey", "2026-02-11T09:00:00Z", None),
+            stream("luge", "Luge", "2026-02-11T11:00:00Z", None),
+        ];
+
+        let schedule = build_schedule(&streams, Tz::UTC, at("2026-02-11T00:00:00Z"));
+        assert_eq!(
+            day_ids(&schedule.days[0]),
+            vec![
+                ("Hockey", vec!["hockey-1", "hockey-2"]),
+                ("Luge", vec!["luge"]),
+                ("Curling", vec!["curling"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_now_markers() {
+        let streams = vec![
+            timed("1", "Hockey", "2026-02-11T09:00:00Z", 180),
+            timed("2", "Curling", "2026-02-11T11:00:00Z", 120),
+            timed("3", "Hockey", "2026-02-12T09:00:00Z", 180),
+        ];
+
+        let schedule = build_schedule(&streams, Tz::UTC, at("2026-02-11T11:30:00Z"));
+        let on_now: Vec<_> = schedule.days[0]
+            .sports
+            .iter()
+            .flat_map(|s| &s.events)
+            .map(|e| (e.stream.id.as_str(), e.is_now))
+            .collect();
+        assert_eq!(on_now, vec![("1", true), ("2", true)]);
+        assert!(schedule.days[0].is_today);
+        assert!(!schedule.days[1].is_today);
+        assert!(!schedule.days[1].sports[0].events[0].is_now);
+    }
+
+    #[test]
+    fn test_gaps_skip_overlaps_and_short_breaks() {
+        let streams = vec![
+            timed("1", "Hockey", "2026-02-11T09:00:00Z", 180),
+            timed("2", "Luge", "2026-02-11T10:00:00Z", 60),
+            timed("3", "Curling", "2026-02-11T12:15:00Z", 105),
+            timed("4", "Biathlon", "2026-02-11T16:00:00Z", 90),
+        ];
+
+        let schedule = build_schedule(&streams, Tz::UTC, at("2026-02-11T15:00:00Z"));
+        assert_eq!(
+            schedule.days[0].gaps,
+            vec![ScheduleGap {
+                start: "2026-02-11T14:00:00+00:00".to_string(),
+                end: "2026-02-11T16:00:00+00:00".to_string(),
+                minutes: 120,
+                is_now: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unreadable_start_times_are_unscheduled() {
+        let streams = vec![
+            stream("1", "Hockey", "soon", None),
+            stream("2", "Hockey", "2026-02-11T09:00:00Z", None),
+        ];
+
+        let schedule = build_schedule(&streams, Tz::UTC, at("2026-02-11T00:00:00Z"));
+        assert_eq!(schedule.days.len(), 1);
+        assert_eq!(schedule.unscheduled, vec![streams[0].clone()]);
+    }
+}
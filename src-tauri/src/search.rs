@@ -0,0 +1,449 @@
+use chrono::{DateTime, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+use crate::{StreamInfo, StreamStatus};
+
+/// Filters for `search_streams`. Every filter left out matches everything.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct StreamQuery {
+    /// Words that must all appear in the title or description.
+    pub text: Option<String>,
+    pub sport: Option<String>,
+    /// Any of these statuses.
+    pub statuses: Vec<StreamStatus>,
+    /// First and last local calendar day, inclusive.
+    pub from_date: Option<NaiveDate>,
+    pub to_date: Option<NaiveDate>,
+    /// Only premium streams, or only streams that aren't.
+    pub premium: Option<bool>,
+    pub sort: SortOrder,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    StartTime,
+    Relevance,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HighlightField {
+    Title,
+    Description,
+}
+
+/// A matched range of a field, in UTF-16 code units so it indexes straight
+/// into a JavaScript string.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    pub field: HighlightField,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchResult {
+    pub stream: StreamInfo,
+    pub score: u32,
+    pub highlights: Vec<Highlight>,
+}
+
+const TITLE_WEIGHT: u32 = 3;
+const DESCRIPTION_WEIGHT: u32 = 1;
+/// Extra weight when a word matches at the start of a word.
+const WORD_START_BONUS: u32 = 1;
+
+/// Lowercases `c` and strips French and other Latin accents, so "Équipe"
+/// and "equipe" match.
+fn fold_char(c: char) -> impl Iterator<Item = char> {
+    let folded: &[char] = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => &['a'],
+        'ç' | 'Ç' => &['c'],
+        'è' | 'é' | 'ê' | 'ë' | 'È' | 'É' | 'Ê' | 'Ë' => &['e'],
+        'ì' | 'í' | 'î' | 'ï' | 'Ì' | 'Í' | 'Î' | 'Ï' => &['i'],
+        'ñ' | 'Ñ' => &['n'],
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => &['o'],
+        'ù' | 'ú' | 'û' | 'ü' | 'Ù' | 'Ú' | 'Û' | 'Ü' => &['u'],
+        'ý' | 'ÿ' | 'Ý' | 'Ÿ' => &['y'],
+        'œ' | 'Œ' => &['o', 'e'],
+        'æ' | 'Æ' => &['a', 'e'],
+        '’' => &['\''],
+        _ => &[],
+    };
+    let lowered = folded.is_empty().then(|| c.to_lowercase());
+    folded.iter().copied().chain(lowered.into_iter().flatten())
+}
+
+pub fn fold(text: &str) -> String {
+    text.chars().flat_map(fold_char).collect()
+}
+
+/// `text` folded, with the UTF-16 range of the original character behind
+/// each folded one.
+struct FoldedText {
+    chars: Vec<char>,
+    spans: Vec<(usize, usize)>,
+}
+
+impl FoldedText {
+    fn new(text: &str) -> Self {
+        let mut chars = Vec::new();
+        let mut spans = Vec::new();
+        let mut offset = 0;
+        for c in text.chars() {
+            let end = offset + c.len_utf16();
+            for folded in fold_char(c) {
+                chars.push(folded);
+                spans.push((offset, end));
+            }
+            offset = end;
+        }
+        Self { chars, spans }
+    }
+
+    /// Original ranges of every occurrence of `term`, and whether each one
+    /// starts a word.
+    fn find(&self, term: &[char]) -> Vec<(usize, usize, bool)> {
+        if term.is_empty() || term.len() > self.chars.len() {
+            return Vec::new();
+        }
+        (0..=self.chars.len() - term.len())
+            .filter(|&i| self.chars[i..i + term.len()] == *term)
+            .map(|i| {
+                let word_start = i == 0 || !self.chars[i - 1].is_alphanumeric();
+                (
+                    self.spans[i].0,
+                    self.spans[i + term.len() - 1].1,
+                    word_start,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Scores `stream` against the query words, or `None` if a word is missing
+/// from both the title and the description.
+fn match_text(stream: &StreamInfo, terms: &[Vec<char>]) -> Option<(u32, Vec<Highlight>)> {
+    let fields = [
+        (
+            HighlightField::Title,
+            FoldedText::new(&stream.title),
+            TITLE_WEIGHT,
+        ),
+        (
+            HighlightField::Description,
+            FoldedText::new(&stream.description),
+            DESCRIPTION_WEIGHT,
+        ),
+    ];
+
+    let mut score = 0;
+    let mut highlights = Vec::new();
+    for term in terms {
+        let mut found = false;
+        for (field, text, weight) in &fields {
+            for (start, end, word_start) in text.find(term) {
+                found = true;
+                score += weight + if word_start { WORD_START_BONUS } else { 0 };
+                highlights.push(Highlight {
+                    field: *field,
+                    start,
+                    end,
+                });
+            }
+        }
+        if !found {
+            return None;
+        }
+    }
+
+    highlights.sort_by_key(|h| (h.field == HighlightField::Description, h.start));
+    highlights.dedup();
+    Some((score, highlights))
+}
+
+fn parse_start<Tz: TimeZone>(stream: &StreamInfo, tz: &Tz) -> Option<DateTime<Tz>> {
+    DateTime::parse_from_rfc3339(&stream.start_time)
+        .ok()
+        .map(|dt| dt.with_timezone(tz))
+}
+
+fn matches_filters<Tz: TimeZone>(stream: &StreamInfo, query: &StreamQuery, tz: &Tz) -> bool {
+    if let Some(sport) = &query.sport {
+        if fold(sport.trim()) != fold(&stream.sport) {
+            return false;
+        }
+    }
+    if !query.statuses.is_empty() && !query.statuses.contains(&stream.status) {
+        return false;
+    }
+    if query
+        .premium
+        .is_some_and(|premium| premium != stream.is_premium)
+    {
+        return false;
+    }
+    if query.from_date.is_some() || query.to_date.is_some() {
+        let Some(day) = parse_start(stream, tz).map(|start| start.date_naive()) else {
+            return false;
+        };
+        if query.from_date.is_some_and(|from| day < from)
+            || query.to_date.is_some_and(|to| day > to)
+        {
+            return false;
+        }
+    }
+    true
+}
+
+/// Streams matching `query`, with calendar days taken in `tz`.
+pub fn search<Tz: TimeZone>(
+    streams: &[StreamInfo],
+    query: &StreamQuery,
+    tz: &Tz,
+) -> Vec<SearchResult> {
+    let terms: Vec<Vec<char>> = query
+        .text
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(|word| word.chars().flat_map(fold_char).collect())
+        .collect();
+
+    let mut results: Vec<SearchResult> = streams
+        .iter()
+        .filter(|stream| matches_filters(stream, query, tz))
+        .filter_map(|stream| {
+            let (score, highlights) = match_text(stream, &terms)?;
+            Some(SearchResult {
+                stream: stream.clone(),
+                score,
+                highlights,
+            })
+        })
+        .collect();
+
+    // Stable sorts, so ties keep catalog order
+    results.sort_by_key(|r| {
+        let start = parse_start(&r.stream, tz).map(|start| start.timestamp());
+        (start.is_none(), start)
+    });
+    if query.sort == SortOrder::Relevance {
+        results.sort_by_key(|r| Reverse(r.score));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn stream(id: &str, title: &str, description: &str, start_time: &str) -> StreamInfo {
+        StreamInfo {
+            title: title.to_string(),
+            description: description.to_string(),
+            start_time: start_time.to_string(),
+            ..StreamInfo::test(id)
+        }
+    }
+
+    fn text_query(text: &str) -> StreamQuery {
+        StreamQuery {
+            text: Some(text.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.stream.id.as_str()).collect()
+    }
+
+    fn eastern() -> FixedOffset {
+        FixedOffset::west_opt(5 * 3600).unwrap()
+    }
+
+    #[test]
+    fn test_fold_strips_accents() {
+        assert_eq!(fold("Équipe Féminine"), "equipe feminine");
+        assert_eq!(fold("Cérémonie d’ouverture"), "ceremonie d'ouverture");
+        assert_eq!(fold("Cœur"), "coeur");
+    }
+
+    #[test]
+    fn test_text_is_accent_insensitive_both_ways() {
+        let streams = vec![
+            stream(
+                "1",
+                "Patinage de vitesse",
+                "Épreuve féminine",
+                "2026-02-10T14:00:00Z",
+            ),
+            stream("2", "Ski alpin", "Descente", "2026-02-10T15:00:00Z"),
+        ];
+
+        let results = search(&streams, &text_query("epreuve"), &eastern());
+        assert_eq!(ids(&results), vec!["1"]);
+
+        let results = search(&streams, &text_query("DESCENTE"), &eastern());
+        assert_eq!(ids(&results), vec!["2"]);
+    }
+
+    #[test]
+    fn test_every_word_must_match() {
+        let streams = vec![
+            stream(
+                "1",
+                "Men's hockey",
+                "Canada vs Sweden",
+                "2026-02-10T14:00:00Z",
+            ),
+            stream(
+                "2",
+                "Women's hockey",
+                "USA vs Finland",
+                "2026-02-10T15:00:00Z",
+            ),
+        ];
+
+        let results = search(&streams, &text_query("hockey canada"), &eastern());
+        assert_eq!(ids(&results), vec!["1"]);
+    }
+
+    #[test]
+    fn test_highlights_point_into_the_original_text() {
+        let streams = vec![stream(
+            "1",
+            "Cérémonie d'ouverture",
+            "La cérémonie",
+            "2026-02-06T19:00:00Z",
+        )];
+
+        let results = search(&streams, &text_query("ceremonie"), &eastern());
+        assert_eq!(
+            results[0].highlights,
+            vec![
+                Highlight {
+                    field: HighlightField::Title,
+                    start: 0,
+                    end: 9,
+                },
+                Highlight {
+                    field: HighlightField::Description,
+                    start: 3,
+                    end: 12,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_highlights_use_utf16_offsets() {
+        let streams = vec![stream("1", "🥇 Final", "", "2026-02-06T19:00:00Z")];
+
+        let results = search(&streams, &text_query("final"), &eastern());
+        assert_eq!(results[0].highlights[0].start, 3);
+        assert_eq!(results[0].highlights[0].end, 8);
+    }
+
+    #[test]
+    fn test_relevance_prefers_title_matches() {
+        let streams = vec![
+            stream(
+                "1",
+                "Curling",
+                "Before the hockey final",
+                "2026-02-10T14:00:00Z",
+            ),
+            stream(
+                "2",
+                "Hockey final",
+                "Gold medal game",
+                "2026-02-11T14:00:00Z",
+            ),
+        ];
+
+        let mut query = text_query("hockey");
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["1", "2"]);
+
+        query.sort = SortOrder::Relevance;
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["2", "1"]);
+    }
+
+    #[test]
+    fn test_sport_status_and_premium_filters() {
+        let mut skating = stream("1", "Short program", "", "2026-02-10T14:00:00Z");
+        skating.sport = "Patinage artistique".to_string();
+        skating.is_premium = true;
+        let mut hockey = stream("2", "Final", "", "2026-02-10T14:00:00Z");
+        hockey.status = StreamStatus::Live;
+        let streams = vec![skating, hockey];
+
+        let query = StreamQuery {
+            sport: Some("patinage ARTISTIQUE".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["1"]);
+
+        let query = StreamQuery {
+            statuses: vec![StreamStatus::Live, StreamStatus::Replay],
+            ..Default::default()
+        };
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["2"]);
+
+        let query = StreamQuery {
+            premium: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["2"]);
+    }
+
+    #[test]
+    fn test_date_filter_uses_local_days() {
+        let streams = vec![
+            // 21:00 on the 9th in Toronto
+            stream("1", "Late game", "", "2026-02-10T02:00:00Z"),
+            stream("2", "Morning game", "", "2026-02-10T14:00:00Z"),
+            stream("3", "Next day", "", "2026-02-11T14:00:00Z"),
+            stream("4", "No date", "", ""),
+        ];
+        let day = |d| NaiveDate::from_ymd_opt(2026, 2, d).unwrap();
+
+        let query = StreamQuery {
+            from_date: Some(day(10)),
+            to_date: Some(day(10)),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["2"]);
+
+        let query = StreamQuery {
+            from_date: Some(day(10)),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["2", "3"]);
+
+        let query = StreamQuery {
+            to_date: Some(day(9)),
+            ..Default::default()
+        };
+        assert_eq!(ids(&search(&streams, &query, &eastern())), vec!["1"]);
+    }
+
+    #[test]
+    fn test_query_deserializes_with_defaults() {
+        let query: StreamQuery = serde_json::from_str(
+            r#"{"text": "hockey", "statuses": ["live"], "from_date": "2026-02-10", "sort": "relevance"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.text.as_deref(), Some("hockey"));
+        assert_eq!(query.statuses, vec![StreamStatus::Live]);
+        assert_eq!(query.from_date, NaiveDate::from_ymd_opt(2026, 2, 10));
+        assert_eq!(query.sort, SortOrder::Relevance);
+        assert!(query.sport.is_none());
+    }
+}
@@ -0,0 +1,161 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{session_store, unix_now, AuthState, HttpClient};
+
+/// Cheap authenticated endpoint used to keep the session alive and to learn
+/// about rotated cookies before a stream request fails.
+const KEEPALIVE_URL: &str =
+    "https://services.radio-canada.ca/ott/subscription/v2/gem/subscriber/profile?device=web";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How long before `expires_at` the frontend is warned.
+const EXPIRING_THRESHOLD: i64 = 30 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExpiringPayload {
+    pub account: String,
+    pub expires_at: i64,
+    pub seconds_left: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionExpiredPayload {
+    pub account: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum SessionHealth {
+    Valid,
+    Expiring,
+    Expired,
+}
+
+fn session_health(expires_at: i64, now: i64) -> SessionHealth {
+    if expires_at <= now {
+        SessionHealth::Expired
+    } else if expires_at - now <= EXPIRING_THRESHOLD {
+        SessionHealth::Expiring
+    } else {
+        SessionHealth::Valid
+    }
+}
+
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Account name -> the expiry we last warned about
+        let mut warned_for: HashMap<String, i64> = HashMap::new();
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            let names = app.state::<AuthState>().accounts.lock().unwrap().names();
+            warned_for.retain(|name, _| names.contains(name));
+            for name in names {
+                check_account(&app, &name, &mut warned_for).await;
+            }
+        }
+    });
+}
+
+async fn check_account(app: &AppHandle, name: &str, warned_for: &mut HashMap<String, i64>) {
+    let state = app.state::<AuthState>();
+    let Some(session) = state.accounts.lock().unwrap().get(name).cloned() else {
+        return;
+    };
+
+    if session_health(session.expires_at, unix_now()) == SessionHealth::Expired {
+        expire_account(app, &state, name);
+        return;
+    }
+
+    let http = app.state::<HttpClient>();
+    let request = http
+        .client()
+        .get(KEEPALIVE_URL)
+        .header("Accept", "application/json");
+    // Rotated cookies are stored back into the account as the response lands
+    let response = http.send_as(app, Some(name), request).await;
+
+    // Offline or CBC hiccup: keep the session and try again next tick
+    let Ok(response) = response else {
+        return;
+    };
+
+    if response.status() == 401 {
+        expire_account(app, &state, name);
+        return;
+    }
+
+    let Some(expires_at) = state
+        .accounts
+        .lock()
+        .unwrap()
+        .get(name)
+        .map(|s| s.expires_at)
+    else {
+        return;
+    };
+
+    let now = unix_now();
+    match session_health(expires_at, now) {
+        SessionHealth::Expiring if warned_for.get(name) != Some(&expires_at) => {
+            warned_for.insert(name.to_string(), expires_at);
+            let _ = app.emit(
+                "cbc-auth-expiring",
+                SessionExpiringPayload {
+                    account: name.to_string(),
+                    expires_at,
+                    seconds_left: expires_at - now,
+                },
+            );
+        }
+        SessionHealth::Expired => expire_account(app, &state, name),
+        _ => {}
+    }
+}
+
+fn expire_account(app: &AppHandle, state: &AuthState, name: &str) {
+    {
+        let mut accounts = state.accounts.lock().unwrap();
+        accounts.remove(name);
+        state.unpin_account(name);
+        if let Err(e) = session_store::persist(app, &accounts) {
+            eprintln!("Failed to clear expired session: {}", e);
+        }
+    }
+    let _ = app.emit(
+        "cbc-auth-expired",
+        SessionExpiredPayload {
+            account: name.to_string(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_health_valid() {
+        assert_eq!(session_health(10_000, 0), SessionHealth::Valid);
+    }
+
+    #[test]
+    fn test_session_health_expiring_within_threshold() {
+        assert_eq!(
+            session_health(EXPIRING_THRESHOLD, 0),
+            SessionHealth::Expiring
+        );
+        assert_eq!(session_health(60, 0), SessionHealth::Expiring);
+    }
+
+    #[test]
+    fn test_session_health_expired() {
+        assert_eq!(session_health(100, 100), SessionHealth::Expired);
+        assert_eq!(session_health(100, 200), SessionHealth::Expired);
+    }
+}
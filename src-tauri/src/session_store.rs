@@ -0,0 +1,224 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+
+use crate::accounts::Accounts;
+use crate::{AuthSession, LoonieError};
+
+/// Store file holding the encrypted accounts blob.
+const STORE_FILE: &str = "session.json";
+const SESSION_ENTRY: &str = "auth_session";
+
+/// The encryption key lives next to the store rather than inside it, so the
+/// webview (which has `store:default`) can never read a usable session.
+const KEY_FILE: &str = "session.key";
+const NONCE_LEN: usize = 12;
+
+/// Loads the persisted accounts, dropping expired sessions and anything
+/// that can no longer be decrypted.
+pub fn load<R: Runtime>(app: &AppHandle<R>) -> Option<Accounts> {
+    let store = app.store(STORE_FILE).ok()?;
+    let blob = store.get(SESSION_ENTRY)?;
+    let key = encryption_key(app).ok()?;
+
+    let Some(mut accounts) = blob.as_str().and_then(|blob| decrypt(&key, blob)) else {
+        let _ = clear(app);
+        return None;
+    };
+
+    if accounts.remove_expired(crate::unix_now()).is_empty() {
+        return Some(accounts);
+    }
+    let _ = persist(app, &accounts);
+    (!accounts.is_empty()).then_some(accounts)
+}
+
+/// Saves the accounts, or clears the store once the last one is gone.
+pub fn persist<R: Runtime>(app: &AppHandle<R>, accounts: &Accounts) -> Result<(), LoonieError> {
+    if accounts.is_empty() {
+        clear(app)
+    } else {
+        save(app, accounts)
+    }
+}
+
+pub fn save<R: Runtime>(app: &AppHandle<R>, accounts: &Accounts) -> Result<(), LoonieError> {
+    let key = encryption_key(app)?;
+    let blob = encrypt(&key, accounts)?;
+
+    let store = app
+        .store(STORE_FILE)
+        .map_err(|e| LoonieError::storage(format!("Failed to open session store: {}", e)))?;
+    store.set(SESSION_ENTRY, blob);
+    store
+        .save()
+        .map_err(|e| LoonieError::storage(format!("Failed to save session store: {}", e)))
+}
+
+pub fn clear<R: Runtime>(app: &AppHandle<R>) -> Result<(), LoonieError> {
+    let store = app
+        .store(STORE_FILE)
+        .map_err(|e| LoonieError::storage(format!("Failed to open session store: {}", e)))?;
+    store.delete(SESSION_ENTRY);
+    store
+        .save()
+        .map_err(|e| LoonieError::storage(format!("Failed to save session store: {}", e)))
+}
+
+fn encryption_key<R: Runtime>(app: &AppHandle<R>) -> Result<Key, LoonieError> {
+    let dir = app.path().app_local_data_dir().map_err(|e| {
+        LoonieError::storage(format!("Failed to resolve app data directory: {}", e))
+    })?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| LoonieError::storage(format!("Failed to create app data directory: {}", e)))?;
+
+    let path = dir.join(KEY_FILE);
+    if let Ok(bytes) = fs::read(&path) {
+        if bytes.len() == 32 {
+            return Ok(*Key::from_slice(&bytes));
+        }
+    }
+
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    write_key_file(&path, &key)
+        .map_err(|e| LoonieError::storage(format!("Failed to write session key: {}", e)))?;
+    Ok(key)
+}
+
+fn write_key_file(path: &Path, key: &Key) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(key.as_slice())
+}
+
+/// Encrypts accounts into a base64 `nonce || ciphertext` blob.
+fn encrypt(key: &Key, accounts: &Accounts) -> Result<String, LoonieError> {
+    let plaintext = serde_json::to_vec(accounts)
+        .map_err(|e| LoonieError::storage(format!("Failed to serialize session: {}", e)))?;
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = ChaCha20Poly1305::new(key)
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| LoonieError::storage("Failed to encrypt session"))?;
+
+    let mut blob = nonce.to_vec();
+    blob.extend_from_slice(&ciphertext);
+    Ok(BASE64.encode(blob))
+}
+
+fn decrypt(key: &Key, blob: &str) -> Option<Accounts> {
+    let bytes = BASE64.decode(blob).ok()?;
+    if bytes.len() <= NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let plaintext = ChaCha20Poly1305::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()?;
+
+    if let Ok(accounts) = serde_json::from_slice(&plaintext) {
+        return Some(accounts);
+    }
+
+    // Blobs written before multiple accounts held a single session
+    let session: AuthSession = serde_json::from_slice(&plaintext).ok()?;
+    let mut accounts = Accounts::default();
+    accounts.insert(accounts.default_name(&session), session);
+    Some(accounts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn test_session() -> AuthSession {
+        AuthSession {
+            cookies: HashMap::from([("session_id".to_string(), "abc123".to_string())]),
+            cookie_meta: vec![],
+            user_id: Some("user123".to_string()),
+            profile: None,
+            expires_at: 1234567890,
+        }
+    }
+
+    fn test_accounts() -> Accounts {
+        let mut accounts = Accounts::default();
+        accounts.insert("Home".to_string(), test_session());
+        accounts
+    }
+
+    fn encrypt_raw(key: &Key, plaintext: &[u8]) -> String {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut blob = nonce.to_vec();
+        blob.extend(
+            ChaCha20Poly1305::new(key)
+                .encrypt(&nonce, plaintext)
+                .unwrap(),
+        );
+        BASE64.encode(blob)
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let blob = encrypt(&key, &test_accounts()).unwrap();
+
+        let accounts = decrypt(&key, &blob).unwrap();
+        assert_eq!(accounts.active_name(), Some("Home"));
+        let session = accounts.active().unwrap();
+        assert_eq!(
+            session.cookies.get("session_id"),
+            Some(&"abc123".to_string())
+        );
+        assert_eq!(session.user_id, Some("user123".to_string()));
+        assert_eq!(session.expires_at, 1234567890);
+    }
+
+    #[test]
+    fn test_decrypt_migrates_single_session_blob() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let blob = encrypt_raw(&key, &serde_json::to_vec(&test_session()).unwrap());
+
+        let accounts = decrypt(&key, &blob).unwrap();
+        assert_eq!(accounts.names(), vec!["user123"]);
+        assert_eq!(accounts.active().unwrap().expires_at, 1234567890);
+    }
+
+    #[test]
+    fn test_encrypted_blob_does_not_contain_cookie_values() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let blob = encrypt(&key, &test_accounts()).unwrap();
+        let raw = BASE64.decode(&blob).unwrap();
+
+        assert!(!blob.contains("abc123"));
+        assert!(!raw.windows(6).any(|w| w == b"abc123"));
+    }
+
+    #[test]
+    fn test_decrypt_with_wrong_key_fails() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let other_key = ChaCha20Poly1305::generate_key(&mut OsRng);
+        let blob = encrypt(&key, &test_accounts()).unwrap();
+
+        assert!(decrypt(&other_key, &blob).is_none());
+    }
+
+    #[test]
+    fn test_decrypt_rejects_garbage() {
+        let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+
+        assert!(decrypt(&key, "not base64!").is_none());
+        assert!(decrypt(&key, &BASE64.encode([0u8; 4])).is_none());
+        assert!(decrypt(&key, &BASE64.encode([0u8; 64])).is_none());
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How long an event of an unknown sport is assumed to run.
+const DEFAULT_EVENT_MINUTES: i64 = 180;
+
+/// Typical running time of a broadcast, in minutes, keyed by lowercase
+/// fragments of the sport name in English or French. Checked in order.
+const TYPICAL_DURATIONS: &[(&[&str], i64)] = &[
+    (&["ceremon", "cérémon"], 240),
+    (&["hockey"], 180),
+    (&["curling"], 180),
+    (&["figure skating", "patinage artistique"], 210),
+    (
+        &[
+            "speed skating",
+            "patinage de vitesse",
+            "short track",
+            "courte piste",
+        ],
+        150,
+    ),
+    (&["ski jumping", "saut à ski"], 120),
+    (&["alpine", "alpin"], 120),
+    (&["cross-country", "cross country", "ski de fond"], 120),
+    (&["biathlon"], 90),
+    (&["bobsleigh", "luge", "skeleton"], 120),
+    (&["snowboard", "freestyle", "acrobatique"], 150),
+    (&["highlights", "faits saillants", "recap"], 30),
+];
+
+/// How long a broadcast of `sport` usually runs, for catalog items without
+/// a duration or broadcast window.
+pub fn typical_duration(sport: &str) -> Duration {
+    let sport = sport.to_lowercase();
+    let minutes = TYPICAL_DURATIONS
+        .iter()
+        .find(|(fragments, _)| fragments.iter().any(|f| sport.contains(f)))
+        .map_or(DEFAULT_EVENT_MINUTES, |(_, minutes)| *minutes);
+    Duration::minutes(minutes)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamStatus {
+    Upcoming,
+    Live,
+    Replay,
+    Ended,
+}
+
+impl StreamStatus {
+    /// Status of a catalog item of `item_type` at `now`. A `Live` event is
+    /// live from its start until its end, even when CBC already offers it
+    /// from the beginning; once over it is a replay if it has VOD and ended
+    /// otherwise. A `Media` item is a recording, so it is never live: it
+    /// goes straight from upcoming to replay or ended.
+    pub fn at(
+        item_type: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+        is_vod_enabled: bool,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let Some(start) = start else {
+            // Without an air date we can only go by VOD availability
+            return if is_vod_enabled {
+                Self::Replay
+            } else {
+                Self::Upcoming
+            };
+        };
+
+        if now < start {
+            return Self::Upcoming;
+        }
+
+        let end = end.unwrap_or(start + Duration::minutes(DEFAULT_EVENT_MINUTES));
+        if now < end && item_type != "Media" {
+            Self::Live
+        } else if is_vod_enabled {
+            Self::Replay
+        } else {
+            Self::Ended
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_status_table() {
+        let start = Some(time("2026-02-10T14:00:00Z"));
+        let end = Some(time("2026-02-10T16:00:00Z"));
+
+        let cases = [
+            // (now, vod, expected)
+            ("2026-02-10T13:59:00Z", false, StreamStatus::Upcoming),
+            ("2026-02-10T13:59:00Z", true, StreamStatus::Upcoming),
+            ("2026-02-10T14:00:00Z", false, StreamStatus::Live),
+            ("2026-02-10T15:30:00Z", false, StreamStatus::Live),
+            // Start-over is available while the event is still on
+            ("2026-02-10T15:30:00Z", true, StreamStatus::Live),
+            ("2026-02-10T16:00:00Z", true, StreamStatus::Replay),
+            ("2026-02-10T16:00:00Z", false, StreamStatus::Ended),
+            ("2026-02-11T09:00:00Z", false, StreamStatus::Ended),
+            ("2026-02-11T09:00:00Z", true, StreamStatus::Replay),
+        ];
+
+        for (now, vod, expected) in cases {
+            assert_eq!(
+                StreamStatus::at("Live", start, end, vod, time(now)),
+                expected,
+                "now={} vod={}",
+                now,
+                vod
+            );
+        }
+    }
+
+    #[test]
+    fn test_status_without_end_time_assumes_default_duration() {
+        let start = Some(time("2026-02-10T14:00:00Z"));
+
+        let cases = [
+            ("2026-02-10T16:59:00Z", false, StreamStatus::Live),
+            ("2026-02-10T17:00:00Z", false, StreamStatus::Ended),
+            ("2026-02-10T17:00:00Z", true, StreamStatus::Replay),
+        ];
+
+        for (now, vod, expected) in cases {
+            assert_eq!(
+                StreamStatus::at("Live", start, None, vod, time(now)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_status_without_air_date() {
+        let now = time("2026-02-10T15:00:00Z");
+        assert_eq!(
+            StreamStatus::at("Live", None, None, true, now),
+            StreamStatus::Replay
+        );
+        assert_eq!(
+            StreamStatus::at("Live", None, None, false, now),
+            StreamStatus::Upcoming
+        );
+    }
+
+    #[test]
+    fn test_media_is_never_live() {
+        let start = Some(time("2026-02-10T14:00:00Z"));
+        let end = Some(time("2026-02-10T16:00:00Z"));
+        let at = |now: &str, vod: bool| StreamStatus::at("Media", start, end, vod, time(now));
+
+        assert_eq!(at("2026-02-10T13:00:00Z", true), StreamStatus::Upcoming);
+        assert_eq!(at("2026-02-10T15:00:00Z", true), StreamStatus::Replay);
+        assert_eq!(at("2026-02-10T15:00:00Z", false), StreamStatus::Ended);
+    }
+
+    #[test]
+    fn test_typical_duration() {
+        assert_eq!(typical_duration("Hockey"), Duration::hours(3));
+        assert_eq!(
+            typical_duration("Patinage artistique"),
+            Duration::minutes(210)
+        );
+        assert_eq!(
+            typical_duration("Men's Short Track"),
+            Duration::minutes(150)
+        );
+        assert_eq!(typical_duration("Opening Ceremony"), Duration::minutes(240));
+        assert_eq!(
+            typical_duration("Underwater Rugby"),
+            Duration::minutes(DEFAULT_EVENT_MINUTES)
+        );
+    }
+
+    #[test]
+    fn test_status_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_string(&StreamStatus::Ended).unwrap(),
+            "\"ended\""
+        );
+        let status: StreamStatus = serde_json::from_str("\"replay\"").unwrap();
+        assert_eq!(status, StreamStatus::Replay);
+    }
+}
@@ -0,0 +1,293 @@
+use serde::Serialize;
+
+use crate::LoonieError;
+
+/// Why the media validation API refused a stream.
+///
+/// Only a couple of `errorCode` values are known for certain (1 is the
+/// geo-block, 35 wants a signed-in user); everything else is recognised
+/// from the message, which comes in English or French.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationFailure {
+    GeoBlocked,
+    LoginRequired,
+    NotEntitled,
+    ConcurrentStreamLimit,
+    NotYetAvailable,
+    Expired,
+    Unknown,
+}
+
+/// What the user can do about a failure.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct RecoveryHint {
+    pub retry: bool,
+    pub relogin: bool,
+    pub upgrade: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct Explanation {
+    pub en: &'static str,
+    pub fr: &'static str,
+}
+
+const GEO_BLOCKED_CODE: i32 = 1;
+const LOGIN_REQUIRED_CODE: i32 = 35;
+
+const VALIDATION_BASE_URL: &str = "https://services.radio-canada.ca/media/validation/v2/";
+
+/// Validation API URL that hands out the HLS manifest for `id_media`.
+pub fn validation_url(id_media: i64) -> String {
+    format!(
+        "{}?appCode=medianetlive&connectionType=hd&deviceType=ipad&idMedia={}&multibitrate=true&output=json&tech=hls&manifestVersion=2&manifestType=desktop",
+        VALIDATION_BASE_URL, id_media
+    )
+}
+
+/// The `idMedia` a stream URL ends with, as in `...-30093`.
+pub fn media_id(stream_url: &str) -> Option<i64> {
+    stream_url
+        .split('-')
+        .next_back()
+        .and_then(|s| s.parse::<i64>().ok())
+}
+
+/// Lowercase message fragments, checked in order.
+const MESSAGE_PATTERNS: &[(ValidationFailure, &[&str])] = &[
+    (
+        ValidationFailure::GeoBlocked,
+        &["geo", "région", "region", "country", "pays", "canada only"],
+    ),
+    (
+        ValidationFailure::ConcurrentStreamLimit,
+        &["concurren", "simultan", "too many", "trop de"],
+    ),
+    (
+        ValidationFailure::NotEntitled,
+        &["premium", "subscri", "abonn", "entitle", "forfait"],
+    ),
+    (
+        ValidationFailure::NotYetAvailable,
+        &[
+            "not yet",
+            "pas encore",
+            "not started",
+            "upcoming",
+            "à venir",
+        ],
+    ),
+    // Ahead of Expired so an expired session isn't read as expired content.
+    // French sign-in wording only: "connect" alone also matches "connection"
+    (
+        ValidationFailure::LoginRequired,
+        &[
+            "session",
+            "login",
+            "log in",
+            "sign in",
+            "connectez",
+            "connecté",
+            "authenti",
+        ],
+    ),
+    (
+        ValidationFailure::Expired,
+        &["expired", "expiré", "no longer", "plus disponible"],
+    ),
+];
+
+impl ValidationFailure {
+    pub fn classify(error_code: i32, message: Option<&str>) -> Self {
+        match error_code {
+            GEO_BLOCKED_CODE => return Self::GeoBlocked,
+            LOGIN_REQUIRED_CODE => return Self::LoginRequired,
+            _ => {}
+        }
+
+        let message = message.unwrap_or_default().to_lowercase();
+        let from_message = MESSAGE_PATTERNS
+            .iter()
+            .find(|(_, fragments)| fragments.iter().any(|f| message.contains(f)))
+            .map(|(failure, _)| *failure);
+        if let Some(failure) = from_message {
+            return failure;
+        }
+
+        // Some failures come back with an HTTP status as the error code
+        match error_code {
+            401 => Self::LoginRequired,
+            403 => Self::NotEntitled,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn explanation(self) -> Explanation {
+        let (en, fr) = match self {
+            Self::GeoBlocked => (
+                "This stream is only available in Canada.",
+                "Cette diffusion est offerte seulement au Canada.",
+            ),
+            Self::LoginRequired => (
+                "Your CBC session is no longer valid. Sign in again to keep watching.",
+                "Votre session CBC n'est plus valide. Reconnectez-vous pour continuer à regarder.",
+            ),
+            Self::NotEntitled => (
+                "Your CBC Gem subscription doesn't include this stream.",
+                "Votre abonnement à ICI TOU.TV / CBC Gem n'inclut pas cette diffusion.",
+            ),
+            Self::ConcurrentStreamLimit => (
+                "Too many streams are playing on this account. Stop one and try again.",
+                "Trop de diffusions sont en cours sur ce compte. Arrêtez-en une et réessayez.",
+            ),
+            Self::NotYetAvailable => (
+                "This stream hasn't started yet.",
+                "Cette diffusion n'a pas encore commencé.",
+            ),
+            Self::Expired => (
+                "This stream is no longer available.",
+                "Cette diffusion n'est plus disponible.",
+            ),
+            Self::Unknown => (
+                "CBC couldn't start this stream.",
+                "CBC n'a pas pu lancer cette diffusion.",
+            ),
+        };
+        Explanation { en, fr }
+    }
+
+    pub fn hint(self) -> RecoveryHint {
+        let (retry, relogin, upgrade) = match self {
+            Self::GeoBlocked | Self::Expired => (false, false, false),
+            Self::LoginRequired => (false, true, false),
+            Self::NotEntitled => (false, false, true),
+            Self::ConcurrentStreamLimit | Self::NotYetAvailable | Self::Unknown => {
+                (true, false, false)
+            }
+        };
+        RecoveryHint {
+            retry,
+            relogin,
+            upgrade,
+        }
+    }
+
+    /// The command error for this failure, made while playing with `account`.
+    pub fn into_error(
+        self,
+        error_code: i32,
+        message: Option<String>,
+        account: &str,
+    ) -> LoonieError {
+        match self {
+            Self::GeoBlocked => LoonieError::GeoBlocked { message },
+            Self::LoginRequired => LoonieError::SessionExpired {
+                account: Some(account.to_string()),
+            },
+            Self::NotEntitled => LoonieError::PremiumRequired { message },
+            Self::ConcurrentStreamLimit => LoonieError::ConcurrentStreamLimit { message },
+            Self::NotYetAvailable | Self::Expired | Self::Unknown => {
+                LoonieError::StreamUnavailable {
+                    error_code,
+                    message,
+                    reason: self,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_codes_win_over_message() {
+        assert_eq!(
+            ValidationFailure::classify(1, Some("Too many streams")),
+            ValidationFailure::GeoBlocked
+        );
+        assert_eq!(
+            ValidationFailure::classify(35, None),
+            ValidationFailure::LoginRequired
+        );
+    }
+
+    #[test]
+    fn test_media_id_from_stream_url() {
+        assert_eq!(
+            media_id("https://gem.cbc.ca/live/hockey-night-in-canada-30093"),
+            Some(30093)
+        );
+        assert_eq!(media_id("https://gem.cbc.ca/live/hockey"), None);
+        assert!(validation_url(30093).contains("idMedia=30093&"));
+    }
+
+    #[test]
+    fn test_unrecognised_failure_is_unknown() {
+        assert_eq!(
+            ValidationFailure::classify(999, Some("Something odd")),
+            ValidationFailure::Unknown
+        );
+        assert_eq!(
+            ValidationFailure::classify(999, None),
+            ValidationFailure::Unknown
+        );
+    }
+
+    #[test]
+    fn test_hints() {
+        assert!(ValidationFailure::LoginRequired.hint().relogin);
+        assert!(ValidationFailure::NotEntitled.hint().upgrade);
+        assert!(ValidationFailure::ConcurrentStreamLimit.hint().retry);
+        assert_eq!(
+            ValidationFailure::GeoBlocked.hint(),
+            RecoveryHint {
+                retry: false,
+                relogin: false,
+                upgrade: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_every_failure_is_explained_in_both_languages() {
+        for failure in [
+            ValidationFailure::GeoBlocked,
+            ValidationFailure::LoginRequired,
+            ValidationFailure::NotEntitled,
+            ValidationFailure::ConcurrentStreamLimit,
+            ValidationFailure::NotYetAvailable,
+            ValidationFailure::Expired,
+            ValidationFailure::Unknown,
+        ] {
+            let explanation = failure.explanation();
+            assert!(!explanation.en.is_empty());
+            assert!(!explanation.fr.is_empty());
+            assert_ne!(explanation.en, explanation.fr);
+        }
+    }
+
+    #[test]
+    fn test_into_error_maps_to_distinct_variants() {
+        let error = ValidationFailure::LoginRequired.into_error(35, None, "Home");
+        assert_eq!(
+            error,
+            LoonieError::SessionExpired {
+                account: Some("Home".to_string())
+            }
+        );
+
+        let error = ValidationFailure::NotYetAvailable.into_error(
+            12,
+            Some("Pas encore".to_string()),
+            "Home",
+        );
+        assert_eq!(error.code(), "STREAM_UNAVAILABLE");
+        assert_eq!(
+            error.validation_failure(),
+            Some(ValidationFailure::NotYetAvailable)
+        );
+    }
+}
@@ -36,6 +36,16 @@ vi.mock("./store/viewportStore", () => ({
   })),
 }));
 
+const mockLoadQualitySettings = vi.fn();
+const mockSetAudioViewport = vi.fn();
+
+vi.mock("./store/qualityStore", () => ({
+  useQualityStore: vi.fn(() => ({
+    loadQualitySettings: mockLoadQualitySettings,
+    setAudioViewport: mockSetAudioViewport,
+  })),
+}));
+
 vi.mock("./lib/keyboardShortcuts", () => ({
   useKeyboardShortcuts: vi.fn(),
 }));
@@ -99,6 +109,13 @@ describe("App", () => {
     expect(container.querySelector(".flex.h-screen.w-screen")).toBeInTheDocument();
   });
 
+  it("loads quality settings and reports the viewport with audio", () => {
+    render(<App />);
+
+    expect(mockLoadQualitySettings).toHaveBeenCalled();
+    expect(mockSetAudioViewport).toHaveBeenCalledWith(0);
+  });
+
   it("hides volume slider on small screens", () => {
     render(<App />);
 
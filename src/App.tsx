@@ -8,6 +8,7 @@ import { VideoGrid } from "./components/VideoGrid/VideoGrid";
 import { useKeyboardShortcuts } from "./lib/keyboardShortcuts";
 import { useAudioStore } from "./store/audioStore";
 import { useAuthStore } from "./store/authStore";
+import { useQualityStore } from "./store/qualityStore";
 import { useViewportStore, ViewportCount } from "./store/viewportStore";
 import { OlympicStream } from "./types";
 
@@ -17,6 +18,7 @@ const App = () => {
   const { viewportWithAudioActive, isMuted, toggleMute, masterVolume, setMasterVolume } =
     useAudioStore();
   const { viewportCount } = useViewportStore();
+  const { loadQualitySettings, setAudioViewport } = useQualityStore();
 
   useKeyboardShortcuts();
 
@@ -24,6 +26,15 @@ const App = () => {
     checkSession();
   }, [checkSession]);
 
+  useEffect(() => {
+    loadQualitySettings();
+  }, [loadQualitySettings]);
+
+  // The backend favours this viewport when splitting the bandwidth budget
+  useEffect(() => {
+    setAudioViewport(viewportWithAudioActive);
+  }, [viewportWithAudioActive, setAudioViewport]);
+
   const handleSelectStream = (stream: OlympicStream, viewportIndex: number) => {
     assignStream(stream, viewportIndex);
   };
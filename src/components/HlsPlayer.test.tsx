@@ -5,6 +5,7 @@ import { OlympicStream, StreamManifest } from "../types";
 import { HlsPlayer } from "./HlsPlayer";
 
 const mockGetStreamManifest = vi.fn();
+const mockCloseStream = vi.fn();
 
 vi.mock("../store/streamStore", () => ({
   useStreamStore: vi.fn((selector: (state: unknown) => unknown) => {
@@ -16,15 +17,18 @@ vi.mock("../store/streamStore", () => ({
       fetchStreams: vi.fn(),
       getStreamById: vi.fn(),
       getStreamManifest: mockGetStreamManifest,
+      closeStream: mockCloseStream,
     });
   }),
 }));
 
 const createMockManifest = (overrides: Partial<StreamManifest> = {}): StreamManifest => ({
   url: "https://example.com/manifest.m3u8",
+  source_url: "https://example.com/manifest.m3u8",
   error_code: 0,
   message: null,
   bitrates: [],
+  playlist: null,
   ...overrides,
 });
 
@@ -40,6 +44,8 @@ const createMockStream = (overrides: Partial<OlympicStream> = {}): OlympicStream
   stream_url: "https://example.com/stream.m3u8",
   requires_auth: true,
   is_premium: true,
+  vod_enabled: false,
+  section: "olympics",
   ...overrides,
 });
 
@@ -57,6 +63,8 @@ describe("HlsPlayer", () => {
     consoleWarnSpy = vi.spyOn(console, "warn").mockImplementation(() => {});
     vi.useFakeTimers({ shouldAdvanceTime: true });
     mockGetStreamManifest.mockReset();
+    mockCloseStream.mockReset();
+    mockCloseStream.mockResolvedValue(undefined);
   });
 
   afterEach(() => {
@@ -155,9 +163,11 @@ describe("HlsPlayer", () => {
     it("displays error when API returns error code", async () => {
       mockGetStreamManifest.mockResolvedValue({
         url: "",
+        source_url: "",
         error_code: 500,
         message: "Stream unavailable",
         bitrates: [],
+        playlist: null,
       });
 
       render(
@@ -351,6 +361,7 @@ describe("HlsPlayer", () => {
       const { rerender } = render(
         <HlsPlayer
           stream={firstStream}
+          viewportIndex={2}
           isAudioActive={true}
           volume={0.5}
           onError={mockOnError}
@@ -359,7 +370,7 @@ describe("HlsPlayer", () => {
       );
 
       await waitFor(() => {
-        expect(mockGetStreamManifest).toHaveBeenCalledWith(firstStream.stream_url);
+        expect(mockGetStreamManifest).toHaveBeenCalledWith(firstStream.stream_url, 2);
       });
 
       mockGetStreamManifest.mockClear();
@@ -370,6 +381,7 @@ describe("HlsPlayer", () => {
       rerender(
         <HlsPlayer
           stream={secondStream}
+          viewportIndex={2}
           isAudioActive={true}
           volume={0.5}
           onError={mockOnError}
@@ -378,11 +390,38 @@ describe("HlsPlayer", () => {
       );
 
       await waitFor(() => {
-        expect(mockGetStreamManifest).toHaveBeenCalledWith(secondStream.stream_url);
+        expect(mockGetStreamManifest).toHaveBeenCalledWith(secondStream.stream_url, 2);
       });
     });
   });
 
+  describe("Stream proxy", () => {
+    it("closes the proxied stream on unmount", async () => {
+      mockGetStreamManifest.mockResolvedValue(
+        createMockManifest({ url: "http://127.0.0.1:4000/abc/master.m3u8" }),
+      );
+
+      const { unmount } = render(
+        <HlsPlayer
+          stream={createMockStream()}
+          isAudioActive={true}
+          volume={0.5}
+          onError={mockOnError}
+          onLoad={mockOnLoad}
+        />,
+      );
+
+      await waitFor(() => {
+        expect(document.querySelector("video")).toBeInTheDocument();
+      });
+      expect(mockCloseStream).not.toHaveBeenCalled();
+
+      unmount();
+
+      expect(mockCloseStream).toHaveBeenCalledWith("http://127.0.0.1:4000/abc/master.m3u8");
+    });
+  });
+
   describe("Component Structure", () => {
     it("renders with correct container classes", async () => {
       mockGetStreamManifest.mockResolvedValue(createMockManifest());
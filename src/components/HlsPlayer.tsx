@@ -1,11 +1,13 @@
 import Hls, { HlsConfig } from "hls.js";
 import { useEffect, useRef, useCallback, useState } from "react";
 
+import { errorMessage } from "../lib/errors";
 import { useStreamStore } from "../store/streamStore";
 import { OlympicStream, StreamManifest } from "../types";
 
 interface HlsPlayerProps {
   stream: OlympicStream;
+  viewportIndex?: number;
   isAudioActive: boolean;
   volume: number;
   onError: (error: string) => void;
@@ -15,6 +17,7 @@ interface HlsPlayerProps {
 
 const HlsPlayer = ({
   stream,
+  viewportIndex,
   isAudioActive,
   volume,
   onError,
@@ -24,6 +27,7 @@ const HlsPlayer = ({
   const videoRef = useRef<HTMLVideoElement>(null);
   const hlsRef = useRef<Hls | null>(null);
   const getStreamManifest = useStreamStore((state) => state.getStreamManifest);
+  const closeStream = useStreamStore((state) => state.closeStream);
 
   // Store callbacks in refs to avoid re-creating the HLS instance when
   // parent re-renders with new inline function references.
@@ -50,6 +54,10 @@ const HlsPlayer = ({
   // Fetch the manifest when stream changes
   useEffect(() => {
     let isCancelled = false;
+    // The backend keeps proxying a stream until it's closed
+    let openedUrl: string | null = null;
+    const release = (url: string) =>
+      closeStream(url).catch((error) => console.error("Failed to close the stream:", error));
 
     const fetchManifest = async () => {
       setIsLoadingManifest(true);
@@ -57,9 +65,13 @@ const HlsPlayer = ({
       setManifestUrl(null);
 
       try {
-        const manifest = await getStreamManifest(stream.stream_url);
+        const manifest = await getStreamManifest(stream.stream_url, viewportIndex);
 
-        if (isCancelled) return;
+        if (isCancelled) {
+          release(manifest.url);
+          return;
+        }
+        openedUrl = manifest.url;
 
         if (manifest.error_code !== 0) {
           setManifestError(manifest.message || "Failed to load stream manifest");
@@ -72,10 +84,9 @@ const HlsPlayer = ({
       } catch (error) {
         if (isCancelled) return;
 
-        const errorMessage =
-          error instanceof Error ? error.message : "Failed to fetch stream manifest";
-        setManifestError(errorMessage);
-        onErrorRef.current(errorMessage);
+        const message = errorMessage(error, "Failed to fetch stream manifest");
+        setManifestError(message);
+        onErrorRef.current(message);
       } finally {
         if (!isCancelled) {
           setIsLoadingManifest(false);
@@ -87,8 +98,11 @@ const HlsPlayer = ({
 
     return () => {
       isCancelled = true;
+      if (openedUrl) {
+        release(openedUrl);
+      }
     };
-  }, [stream.stream_url, getStreamManifest]);
+  }, [stream.stream_url, viewportIndex, getStreamManifest, closeStream]);
 
   const isVodStream = useCallback((url: string): boolean => {
     // VOD streams have startTime and endTime parameters (archive streams)
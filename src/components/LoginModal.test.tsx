@@ -4,15 +4,16 @@ import { render, screen, waitFor, within } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
 
-import { AuthSession } from "../types";
+import { SessionSummary } from "../types";
 import { LoginModal } from "./LoginModal";
 
 // Create mock functions that can be reset between tests
 const mockLogin = vi.fn();
 
-const mockSession: AuthSession = {
-  cookies: { test: "value" },
+const mockSession: SessionSummary = {
+  signed_in: true,
   user_id: "user123",
+  display_name: "Test User",
   expires_at: Date.now() / 1000 + 3600,
 };
 
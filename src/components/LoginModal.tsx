@@ -4,7 +4,7 @@ import { User, ExternalLink, Info, Loader2, X, AlertCircle, CheckCircle } from "
 import { useState, useEffect, useCallback } from "react";
 
 import { useAuthStore } from "../store/authStore";
-import { AuthSession } from "../types";
+import { SessionSummary } from "../types";
 
 type AuthStatus =
   | "idle"
@@ -24,7 +24,7 @@ const LoginModal = () => {
 
   // Listen for auth events from Rust backend
   useEffect(() => {
-    const unlistenSuccess = listen<AuthSession>("cbc-auth-success", (event) => {
+    const unlistenSuccess = listen<SessionSummary>("cbc-auth-success", (event) => {
       setAuthStatus("extracting");
       // Store the session
       login(event.payload)
@@ -0,0 +1,31 @@
+import { render, screen } from "@testing-library/react";
+import userEvent from "@testing-library/user-event";
+import { describe, expect, it, vi } from "vitest";
+
+import { ReminderButton } from "./ReminderButton";
+
+describe("ReminderButton", () => {
+  it("offers a reminder when none is set", () => {
+    render(<ReminderButton isSet={false} onToggle={vi.fn()} />);
+
+    const button = screen.getByTitle("Remind me");
+    expect(button).toHaveAttribute("aria-pressed", "false");
+  });
+
+  it("offers to cancel a set reminder", () => {
+    render(<ReminderButton isSet={true} onToggle={vi.fn()} />);
+
+    const button = screen.getByTitle("Cancel reminder");
+    expect(button).toHaveAttribute("aria-pressed", "true");
+  });
+
+  it("calls onToggle when clicked", async () => {
+    const user = userEvent.setup();
+    const onToggle = vi.fn();
+
+    render(<ReminderButton isSet={false} onToggle={onToggle} />);
+    await user.click(screen.getByRole("button"));
+
+    expect(onToggle).toHaveBeenCalledTimes(1);
+  });
+});
@@ -0,0 +1,21 @@
+import { Star } from "lucide-react";
+
+interface ReminderButtonProps {
+  isSet: boolean;
+  onToggle: () => void;
+}
+
+const ReminderButton = ({ isSet, onToggle }: ReminderButtonProps) => {
+  return (
+    <button
+      onClick={onToggle}
+      className="p-1 text-slate-400 transition-colors hover:text-yellow-400"
+      title={isSet ? "Cancel reminder" : "Remind me"}
+      aria-pressed={isSet}
+    >
+      <Star className={`h-4 w-4 ${isSet ? "fill-yellow-400 text-yellow-400" : ""}`} />
+    </button>
+  );
+};
+
+export { ReminderButton };
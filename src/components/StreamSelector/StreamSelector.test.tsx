@@ -1,8 +1,10 @@
-import { render, screen } from "@testing-library/react";
+import * as eventModule from "@tauri-apps/api/event";
+import { render, screen, waitFor } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
 import { describe, expect, it, vi, beforeEach, afterEach } from "vitest";
 
 import { useAuthStore } from "../../store/authStore";
+import { useReminderStore } from "../../store/reminderStore";
 import { useStreamStore } from "../../store/streamStore";
 import { useUIStore } from "../../store/uiStore";
 import { OlympicStream } from "../../types";
@@ -10,6 +12,7 @@ import { StreamSelector } from "./StreamSelector";
 
 // Mock the stores
 vi.mock("../../store/authStore");
+vi.mock("../../store/reminderStore");
 vi.mock("../../store/streamStore");
 vi.mock("../../store/uiStore");
 
@@ -26,6 +29,8 @@ const createMockStream = (overrides: Partial<OlympicStream> = {}): OlympicStream
   stream_url: "https://example.com/stream.m3u8",
   requires_auth: true,
   is_premium: true,
+  vod_enabled: false,
+  section: "olympics",
   ...overrides,
 });
 
@@ -44,19 +49,24 @@ const mockUpcomingStreams = [
 
 describe("StreamSelector", () => {
   const mockFetchStreams = vi.fn();
+  const mockApplyCatalogEvent = vi.fn();
+  const mockExportCalendar = vi.fn();
+  const mockTakeStreamLink = vi.fn();
+  const mockLoadReminders = vi.fn();
+  const mockSetReminders = vi.fn();
+  const mockAddReminder = vi.fn();
+  const mockCancelReminder = vi.fn();
   const mockToggleSidebar = vi.fn();
 
   beforeEach(() => {
     vi.clearAllMocks();
+    (eventModule as any).__clearListeners();
     vi.useFakeTimers({ shouldAdvanceTime: true });
+    mockTakeStreamLink.mockResolvedValue(null);
 
     // Set up default mock implementations
     (useAuthStore as any).mockReturnValue({
-      session: {
-        cookies: { test: "value" },
-        user_id: "user123",
-        expires_at: Date.now() / 1000 + 3600,
-      },
+      isAuthenticated: true,
     });
 
     (useStreamStore as any).mockReturnValue({
@@ -65,6 +75,17 @@ describe("StreamSelector", () => {
       error: null,
       lastUpdated: new Date("2024-01-01T12:00:00"),
       fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
+      applyCatalogEvent: mockApplyCatalogEvent,
+      exportCalendar: mockExportCalendar,
+    });
+
+    (useReminderStore as any).mockReturnValue({
+      loadReminders: mockLoadReminders,
+      setReminders: mockSetReminders,
+      addReminder: mockAddReminder,
+      cancelReminder: mockCancelReminder,
+      hasReminder: (streamId: string) => streamId === "stream-4",
     });
 
     (useUIStore as any).mockReturnValue({
@@ -157,6 +178,7 @@ describe("StreamSelector", () => {
       error: null,
       lastUpdated: null,
       fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
     });
 
     const onSelectStream = vi.fn();
@@ -175,6 +197,7 @@ describe("StreamSelector", () => {
       error: errorMessage,
       lastUpdated: null,
       fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
     });
 
     const onSelectStream = vi.fn();
@@ -189,12 +212,12 @@ describe("StreamSelector", () => {
 
     render(<StreamSelector onSelectStream={onSelectStream} selectedViewport={0} />);
 
-    expect(mockFetchStreams).toHaveBeenCalledWith({ test: "value" });
+    expect(mockFetchStreams).toHaveBeenCalledWith();
   });
 
   it("does not fetch streams when session is not available", () => {
     (useAuthStore as any).mockReturnValue({
-      session: null,
+      isAuthenticated: false,
     });
 
     const onSelectStream = vi.fn();
@@ -204,36 +227,108 @@ describe("StreamSelector", () => {
     expect(mockFetchStreams).not.toHaveBeenCalled();
   });
 
-  it("refreshes streams every 2 minutes", () => {
+  it("applies catalog events from the backend instead of polling", async () => {
     const onSelectStream = vi.fn();
 
     render(<StreamSelector onSelectStream={onSelectStream} selectedViewport={0} />);
 
     expect(mockFetchStreams).toHaveBeenCalledTimes(1);
 
-    vi.advanceTimersByTime(120000);
+    const stream = createMockStream({ id: "5", status: "live" });
+    await eventModule.emit("stream-went-live", stream);
 
-    expect(mockFetchStreams).toHaveBeenCalledTimes(2);
+    expect(mockApplyCatalogEvent).toHaveBeenCalledWith("stream-went-live", stream);
 
     vi.advanceTimersByTime(120000);
 
-    expect(mockFetchStreams).toHaveBeenCalledTimes(3);
+    expect(mockFetchStreams).toHaveBeenCalledTimes(1);
+  });
+
+  it("opens the stream behind a calendar link", async () => {
+    const onSelectStream = vi.fn();
+
+    render(<StreamSelector onSelectStream={onSelectStream} selectedViewport={2} />);
+
+    const stream = createMockStream({ id: "6", status: "upcoming" });
+    await eventModule.emit("open-stream", stream);
+
+    expect(onSelectStream).toHaveBeenCalledWith(stream, 2);
+  });
+
+  it("opens the stream the app was launched with once mounted", async () => {
+    const onSelectStream = vi.fn();
+    const stream = createMockStream({ id: "7", status: "upcoming" });
+    mockTakeStreamLink.mockResolvedValueOnce(stream);
+
+    render(<StreamSelector onSelectStream={onSelectStream} selectedViewport={1} />);
+
+    await waitFor(() => expect(onSelectStream).toHaveBeenCalledWith(stream, 1));
+    expect(mockTakeStreamLink).toHaveBeenCalled();
+  });
+
+  it("exports live and upcoming streams to a calendar", async () => {
+    const user = userEvent.setup();
+
+    render(<StreamSelector onSelectStream={vi.fn()} selectedViewport={0} />);
+
+    await user.click(screen.getByTitle("Export schedule to calendar"));
+
+    expect(mockExportCalendar).toHaveBeenCalledWith([...mockLiveStreams, ...mockUpcomingStreams]);
+  });
+
+  it("loads reminders and applies changes from the backend", async () => {
+    render(<StreamSelector onSelectStream={vi.fn()} selectedViewport={0} />);
+
+    expect(mockLoadReminders).toHaveBeenCalledTimes(1);
+
+    const reminders = [
+      {
+        stream_id: "stream-4",
+        title: "Upcoming Stream 1",
+        sport: "Skiing",
+        start_time: "2024-01-01T00:00:00Z",
+        lead_minutes: 10,
+        notified: false,
+      },
+    ];
+    await eventModule.emit("reminders-changed", reminders);
+
+    expect(mockSetReminders).toHaveBeenCalledWith(reminders);
+  });
+
+  it("toggles reminders on upcoming streams", async () => {
+    const user = userEvent.setup();
+    const upcoming = createMockStream({ id: "5", title: "Upcoming Stream 2", status: "upcoming" });
+
+    (useStreamStore as any).mockReturnValue({
+      streams: [...mockUpcomingStreams, upcoming],
+      isLoading: false,
+      error: null,
+      lastUpdated: new Date(),
+      fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
+    });
+
+    render(<StreamSelector onSelectStream={vi.fn()} selectedViewport={0} />);
+
+    await user.click(screen.getByTitle("Cancel reminder"));
+    expect(mockCancelReminder).toHaveBeenCalledWith("stream-4");
+
+    await user.click(screen.getByTitle("Remind me"));
+    expect(mockAddReminder).toHaveBeenCalledWith(upcoming);
   });
 
-  it("clears interval on unmount", () => {
+  it("stops listening for catalog events on unmount", async () => {
     const onSelectStream = vi.fn();
 
     const { unmount } = render(
       <StreamSelector onSelectStream={onSelectStream} selectedViewport={0} />,
     );
 
-    expect(mockFetchStreams).toHaveBeenCalledTimes(1);
-
     unmount();
+    await Promise.resolve();
 
-    vi.advanceTimersByTime(120000);
-
-    expect(mockFetchStreams).toHaveBeenCalledTimes(1);
+    expect((eventModule as any).__getMockUnlisten()).toHaveBeenCalled();
   });
 
   it("displays correct last updated time", () => {
@@ -244,6 +339,24 @@ describe("StreamSelector", () => {
     expect(screen.getByText(/Updated:/)).toHaveTextContent("Updated: 12:00:00 PM");
   });
 
+  it("marks a saved catalog as offline", () => {
+    (useStreamStore as any).mockReturnValue({
+      streams: mockLiveStreams,
+      isLoading: false,
+      error: null,
+      lastUpdated: new Date("2024-01-01T12:00:00"),
+      isStale: true,
+      fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
+    });
+
+    const onSelectStream = vi.fn();
+
+    render(<StreamSelector onSelectStream={onSelectStream} selectedViewport={0} />);
+
+    expect(screen.getByText(/Updated:/)).toHaveTextContent("Updated: 12:00:00 PM (offline)");
+  });
+
   it("does not show last updated time when lastUpdated is null", () => {
     (useStreamStore as any).mockReturnValue({
       streams: mockLiveStreams,
@@ -251,6 +364,7 @@ describe("StreamSelector", () => {
       error: null,
       lastUpdated: null,
       fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
     });
 
     const onSelectStream = vi.fn();
@@ -296,6 +410,7 @@ describe("StreamSelector", () => {
       error: null,
       lastUpdated: new Date(),
       fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
     });
 
     const onSelectStream = vi.fn();
@@ -312,6 +427,7 @@ describe("StreamSelector", () => {
       error: null,
       lastUpdated: new Date(),
       fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
     });
 
     const onSelectStream = vi.fn();
@@ -328,6 +444,7 @@ describe("StreamSelector", () => {
       error: null,
       lastUpdated: new Date(),
       fetchStreams: mockFetchStreams,
+      takeStreamLink: mockTakeStreamLink,
     });
 
     const onSelectStream = vi.fn();
@@ -374,7 +491,7 @@ describe("StreamSelector", () => {
     const onSelectStream = vi.fn();
 
     (useAuthStore as any).mockReturnValue({
-      session: null,
+      isAuthenticated: false,
     });
 
     const { rerender } = render(
@@ -384,15 +501,11 @@ describe("StreamSelector", () => {
     expect(mockFetchStreams).not.toHaveBeenCalled();
 
     (useAuthStore as any).mockReturnValue({
-      session: {
-        cookies: { new: "value" },
-        user_id: "user123",
-        expires_at: Date.now() / 1000 + 3600,
-      },
+      isAuthenticated: true,
     });
 
     rerender(<StreamSelector onSelectStream={onSelectStream} selectedViewport={0} />);
 
-    expect(mockFetchStreams).toHaveBeenCalledWith({ new: "value" });
+    expect(mockFetchStreams).toHaveBeenCalledWith();
   });
 });
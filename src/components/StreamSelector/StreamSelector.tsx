@@ -1,33 +1,107 @@
-import { ChevronLeft, ChevronRight, Clock, Loader2, Radio, RotateCcw } from "lucide-react";
+import {
+  CalendarPlus,
+  ChevronLeft,
+  ChevronRight,
+  Clock,
+  Loader2,
+  Radio,
+  RotateCcw,
+} from "lucide-react";
+import { listen } from "@tauri-apps/api/event";
 import { useEffect } from "react";
 
 import { useAuthStore } from "../../store/authStore";
+import { useReminderStore } from "../../store/reminderStore";
 import { useStreamStore } from "../../store/streamStore";
 import { useUIStore } from "../../store/uiStore";
-import { OlympicStream } from "../../types";
+import { CatalogEvent, OlympicStream, Reminder } from "../../types";
+import { ReminderButton } from "./ReminderButton";
 import { StreamCard } from "./StreamCard";
 
+const CATALOG_EVENTS: CatalogEvent[] = [
+  "stream-added",
+  "stream-removed",
+  "stream-went-live",
+  "stream-ended",
+  "stream-replay-available",
+];
+
 interface StreamSelectorProps {
   onSelectStream: (stream: OlympicStream, viewportIndex: number) => void;
   selectedViewport: number;
 }
 
 const StreamSelector = ({ onSelectStream, selectedViewport }: StreamSelectorProps) => {
-  const { streams, isLoading, error, lastUpdated, fetchStreams } = useStreamStore();
-  const { session } = useAuthStore();
+  const {
+    streams,
+    isLoading,
+    error,
+    lastUpdated,
+    isStale,
+    fetchStreams,
+    applyCatalogEvent,
+    exportCalendar,
+    takeStreamLink,
+  } = useStreamStore();
+  const { isAuthenticated } = useAuthStore();
+  const { loadReminders, setReminders, addReminder, cancelReminder, hasReminder } =
+    useReminderStore();
   const { isSidebarCollapsed, toggleSidebar } = useUIStore();
 
   useEffect(() => {
-    if (session?.cookies) {
-      fetchStreams(session.cookies);
+    if (isAuthenticated) {
+      fetchStreams();
+
+      // The backend watches the catalog and reports what changed
+      const unlisteners = CATALOG_EVENTS.map((name) =>
+        listen<OlympicStream>(name, (event) => applyCatalogEvent(name, event.payload)),
+      );
+
+      return () => {
+        unlisteners.forEach((unlisten) => unlisten.then((f) => f()));
+      };
+    }
+  }, [isAuthenticated, fetchStreams, applyCatalogEvent]);
+
+  useEffect(() => {
+    if (isAuthenticated) {
+      loadReminders();
 
-      const interval = setInterval(() => {
-        fetchStreams(session.cookies);
-      }, 120000);
+      // Reminders are completed or rescheduled by the backend as the catalog changes
+      const unlisten = listen<Reminder[]>("reminders-changed", (event) =>
+        setReminders(event.payload),
+      );
 
-      return () => clearInterval(interval);
+      return () => {
+        unlisten.then((f) => f());
+      };
     }
-  }, [session, fetchStreams]);
+  }, [isAuthenticated, loadReminders, setReminders]);
+
+  // Links from exported calendar events open their stream. The link the app
+  // was launched with came in before anything listened, so it's asked for;
+  // the backend hands it out only once
+  useEffect(() => {
+    if (isAuthenticated) {
+      takeStreamLink()
+        .then((stream) => {
+          if (stream) {
+            onSelectStream(stream, selectedViewport);
+          }
+        })
+        .catch((error) => console.error("Failed to open the stream link:", error));
+    }
+  }, [isAuthenticated, takeStreamLink, onSelectStream, selectedViewport]);
+
+  useEffect(() => {
+    const unlisten = listen<OlympicStream>("open-stream", (event) =>
+      onSelectStream(event.payload, selectedViewport),
+    );
+
+    return () => {
+      unlisten.then((f) => f());
+    };
+  }, [onSelectStream, selectedViewport]);
 
   const liveStreams = streams.filter((s) => s.status === "live");
   const replayStreams = streams.filter((s) => s.status === "replay");
@@ -59,8 +133,17 @@ const StreamSelector = ({ onSelectStream, selectedViewport }: StreamSelectorProp
           <h3 className="text-md font-semibold text-white">Olympic Streams</h3>
           <p className="text-sm text-slate-400">
             {lastUpdated && `Updated: ${lastUpdated.toLocaleTimeString()}`}
+            {lastUpdated && isStale && " (offline)"}
           </p>
         </div>
+        <button
+          onClick={() => exportCalendar([...liveStreams, ...upcomingStreams])}
+          disabled={liveStreams.length + upcomingStreams.length === 0}
+          className="mr-3 p-1 text-slate-400 transition-colors hover:text-white disabled:opacity-50"
+          title="Export schedule to calendar"
+        >
+          <CalendarPlus className="h-5 w-5" />
+        </button>
       </div>
 
       <div className="group relative flex h-full">
@@ -126,11 +209,20 @@ const StreamSelector = ({ onSelectStream, selectedViewport }: StreamSelectorProp
               </h4>
               <div className="space-y-2">
                 {upcomingStreams.map((stream) => (
-                  <StreamCard
-                    key={stream.id}
-                    stream={stream}
-                    onClick={() => onSelectStream(stream, selectedViewport)}
-                  />
+                  <div key={stream.id} className="relative">
+                    <StreamCard
+                      stream={stream}
+                      onClick={() => onSelectStream(stream, selectedViewport)}
+                    />
+                    <div className="absolute bottom-2 right-2">
+                      <ReminderButton
+                        isSet={hasReminder(stream.id)}
+                        onToggle={() =>
+                          hasReminder(stream.id) ? cancelReminder(stream.id) : addReminder(stream)
+                        }
+                      />
+                    </div>
+                  </div>
                 ))}
               </div>
             </div>
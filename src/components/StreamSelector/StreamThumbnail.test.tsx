@@ -17,6 +17,8 @@ const createMockStream = (overrides: Partial<OlympicStream> = {}): OlympicStream
   stream_url: "https://example.com/stream.m3u8",
   requires_auth: true,
   is_premium: true,
+  vod_enabled: false,
+  section: "olympics",
   ...overrides,
 });
 
@@ -33,6 +33,8 @@ const createMockStream = (id: string, title: string): OlympicStream => ({
   stream_url: "",
   requires_auth: false,
   is_premium: false,
+  vod_enabled: false,
+  section: "olympics",
 });
 
 describe("VideoGrid", () => {
@@ -24,6 +24,8 @@ const mockStream: OlympicStream = {
   stream_url: "https://example.com/stream.m3u8",
   requires_auth: false,
   is_premium: false,
+  vod_enabled: false,
+  section: "olympics",
 };
 
 describe("Viewport", () => {
@@ -60,6 +60,7 @@ const Viewport = ({
       <div className="flex aspect-video max-h-full w-full items-center justify-center">
         <HlsPlayer
           stream={stream}
+          viewportIndex={index}
           isAudioActive={isAudioActive}
           volume={volume}
           onError={(err) => console.error("Player error:", err)}
@@ -0,0 +1,35 @@
+import { describe, expect, it } from "vitest";
+
+import { errorMessage, isLoonieError } from "./errors";
+
+describe("isLoonieError", () => {
+  it("recognizes backend errors", () => {
+    expect(isLoonieError({ code: "NOT_AUTHENTICATED", message: "Not authenticated" })).toBe(true);
+  });
+
+  it("rejects other values", () => {
+    expect(isLoonieError("Not authenticated")).toBe(false);
+    expect(isLoonieError(null)).toBe(false);
+    expect(isLoonieError({ message: "no code" })).toBe(false);
+  });
+});
+
+describe("errorMessage", () => {
+  it("uses the message of a backend error", () => {
+    const error = {
+      code: "CATALOG_UNAVAILABLE",
+      message: "Catalog API returned status: 503",
+      status: 503,
+    };
+    expect(errorMessage(error, "fallback")).toBe("Catalog API returned status: 503");
+  });
+
+  it("uses the message of an Error", () => {
+    expect(errorMessage(new Error("boom"), "fallback")).toBe("boom");
+  });
+
+  it("falls back for anything else", () => {
+    expect(errorMessage("String error", "fallback")).toBe("fallback");
+    expect(errorMessage(undefined, "fallback")).toBe("fallback");
+  });
+});
@@ -0,0 +1,15 @@
+import { LoonieError } from "../types";
+
+export const isLoonieError = (error: unknown): error is LoonieError =>
+  typeof error === "object" &&
+  error !== null &&
+  typeof (error as LoonieError).code === "string" &&
+  typeof (error as LoonieError).message === "string";
+
+// Message to show for a rejected invoke or any other thrown value
+export const errorMessage = (error: unknown, fallback: string): string => {
+  if (error instanceof Error || isLoonieError(error)) {
+    return error.message;
+  }
+  return fallback;
+};
@@ -1,19 +1,27 @@
 import { invoke } from "@tauri-apps/api/core";
 import { beforeEach, describe, expect, it, vi } from "vitest";
 
-import type { AuthSession } from "../types";
+import type { SessionSummary } from "../types";
 
 import { useAuthStore } from "./authStore";
 
 const mockInvoke = invoke as unknown as ReturnType<typeof vi.fn>;
 
 describe("useAuthStore", () => {
-  const mockSession: AuthSession = {
-    cookies: { session_id: "test-session" },
+  const mockSession: SessionSummary = {
+    signed_in: true,
     user_id: "123",
+    display_name: "Test User",
     expires_at: Date.now() / 1000 + 3600,
   };
 
+  const signedOut: SessionSummary = {
+    signed_in: false,
+    user_id: null,
+    display_name: null,
+    expires_at: null,
+  };
+
   beforeEach(() => {
     mockInvoke.mockClear();
     useAuthStore.setState({
@@ -23,18 +31,22 @@ describe("useAuthStore", () => {
   });
 
   describe("login", () => {
-    it("sets auth session via backend and updates store", async () => {
-      mockInvoke.mockResolvedValue(undefined);
-
+    it("updates store from the backend session summary", async () => {
       await useAuthStore.getState().login(mockSession);
 
-      expect(mockInvoke).toHaveBeenCalledWith("set_auth_session", {
-        session: mockSession,
-      });
+      expect(mockInvoke).not.toHaveBeenCalled();
       const state = useAuthStore.getState();
       expect(state.isAuthenticated).toBe(true);
       expect(state.session).toEqual(mockSession);
     });
+
+    it("stays signed out for a signed-out summary", async () => {
+      await useAuthStore.getState().login(signedOut);
+
+      const state = useAuthStore.getState();
+      expect(state.isAuthenticated).toBe(false);
+      expect(state.session).toBeNull();
+    });
   });
 
   describe("logout", () => {
@@ -54,117 +66,60 @@ describe("useAuthStore", () => {
     });
   });
 
-  describe("checkSession", () => {
-    describe("with valid persisted session", () => {
-      beforeEach(() => {
-        useAuthStore.setState({ isAuthenticated: true, session: mockSession });
-      });
+  describe("importSession", () => {
+    it("signs in with the imported session", async () => {
+      mockInvoke.mockResolvedValue(mockSession);
+      const source = { kind: "cookies_txt" as const, path: "/tmp/cookies.txt" };
 
-      it("restores session when backend confirms it", async () => {
-        mockInvoke.mockResolvedValue(mockSession);
+      await useAuthStore.getState().importSession(source);
 
-        await useAuthStore.getState().checkSession();
-
-        expect(mockInvoke).toHaveBeenCalledWith("check_auth_status");
-        expect(mockInvoke).toHaveBeenCalledWith("set_auth_session", {
-          session: mockSession,
-        });
-        const state = useAuthStore.getState();
-        expect(state.isAuthenticated).toBe(true);
-        expect(state.session).toEqual(mockSession);
-      });
-
-      it("keeps persisted session when backend returns null", async () => {
-        let callCount = 0;
-        mockInvoke.mockImplementation(() => {
-          callCount++;
-          if (callCount === 1) return Promise.resolve(undefined);
-          if (callCount === 2) return Promise.resolve(null);
-          if (callCount === 3) return Promise.resolve(null);
-          return Promise.resolve(undefined);
-        });
-
-        await useAuthStore.getState().checkSession();
-
-        expect(mockInvoke).toHaveBeenCalledWith("set_auth_session", {
-          session: mockSession,
-        });
-        expect(mockInvoke).toHaveBeenCalledWith("check_auth_status");
-        const state = useAuthStore.getState();
-        expect(state.isAuthenticated).toBe(true);
-        expect(state.session).toEqual(mockSession);
-      });
+      expect(mockInvoke).toHaveBeenCalledWith("import_session", { source });
+      const state = useAuthStore.getState();
+      expect(state.isAuthenticated).toBe(true);
+      expect(state.session).toEqual(mockSession);
     });
 
-    describe("with expired persisted session", () => {
-      const expiredSession: AuthSession = {
-        ...mockSession,
-        expires_at: Date.now() / 1000 - 3600,
-      };
-
-      beforeEach(() => {
-        useAuthStore.setState({
-          isAuthenticated: true,
-          session: expiredSession,
-        });
-      });
-
-      it("clears auth state and calls logout", async () => {
-        mockInvoke.mockResolvedValue(undefined);
-
-        await useAuthStore.getState().checkSession();
+    it("rethrows when the import fails", async () => {
+      mockInvoke.mockRejectedValue("CBC did not accept the imported cookies");
 
-        expect(mockInvoke).toHaveBeenCalledWith("logout");
-        const state = useAuthStore.getState();
-        expect(state.isAuthenticated).toBe(false);
-        expect(state.session).toBeNull();
-      });
+      await expect(
+        useAuthStore.getState().importSession({ kind: "firefox", profile: "/tmp/profile" }),
+      ).rejects.toBe("CBC did not accept the imported cookies");
+      expect(useAuthStore.getState().isAuthenticated).toBe(false);
     });
+  });
 
-    describe("with no persisted session", () => {
-      it("sets auth state when backend returns valid session", async () => {
-        mockInvoke.mockResolvedValue(mockSession);
+  describe("checkSession", () => {
+    it("sets auth state when backend reports a signed-in session", async () => {
+      mockInvoke.mockResolvedValue(mockSession);
 
-        await useAuthStore.getState().checkSession();
+      await useAuthStore.getState().checkSession();
 
-        expect(mockInvoke).toHaveBeenCalledWith("check_auth_status");
-        const state = useAuthStore.getState();
-        expect(state.isAuthenticated).toBe(true);
-        expect(state.session).toEqual(mockSession);
-      });
+      expect(mockInvoke).toHaveBeenCalledWith("check_auth_status");
+      const state = useAuthStore.getState();
+      expect(state.isAuthenticated).toBe(true);
+      expect(state.session).toEqual(mockSession);
+    });
 
-      it("keeps not authenticated when backend returns invalid session", async () => {
-        mockInvoke.mockResolvedValue(null);
+    it("clears auth state when backend reports no session", async () => {
+      useAuthStore.setState({ isAuthenticated: true, session: mockSession });
+      mockInvoke.mockResolvedValue(signedOut);
 
-        await useAuthStore.getState().checkSession();
+      await useAuthStore.getState().checkSession();
 
-        expect(mockInvoke).toHaveBeenCalledWith("check_auth_status");
-        const state = useAuthStore.getState();
-        expect(state.isAuthenticated).toBe(false);
-        expect(state.session).toBeNull();
-      });
+      expect(mockInvoke).toHaveBeenCalledWith("check_auth_status");
+      const state = useAuthStore.getState();
+      expect(state.isAuthenticated).toBe(false);
+      expect(state.session).toBeNull();
+    });
 
-      it("clears auth state when backend session is expired", async () => {
-        const expiredSession: AuthSession = {
-          ...mockSession,
-          expires_at: Date.now() / 1000 - 3600,
-        };
-        let callCount = 0;
-        mockInvoke.mockImplementation(() => {
-          callCount++;
-          if (callCount === 1) return Promise.resolve(expiredSession);
-          if (callCount === 2) return Promise.resolve(undefined);
-          return Promise.resolve(undefined);
-        });
+    it("never sends a session to the backend", async () => {
+      mockInvoke.mockResolvedValue(mockSession);
 
-        await useAuthStore.getState().checkSession();
+      await useAuthStore.getState().checkSession();
 
-        expect(mockInvoke).toHaveBeenCalledWith("check_auth_status");
-        expect(mockInvoke).toHaveBeenCalledWith("logout");
-        const state = useAuthStore.getState();
-        expect(state.isAuthenticated).toBe(false);
-        expect(state.session).toBeNull();
-      });
+      expect(mockInvoke).toHaveBeenCalledTimes(1);
+      expect(mockInvoke).not.toHaveBeenCalledWith("set_auth_session", expect.anything());
     });
 
     describe("error handling", () => {
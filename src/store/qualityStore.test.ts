@@ -0,0 +1,69 @@
+import { invoke } from "@tauri-apps/api/core";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+import { QualitySettings } from "../types";
+import { useQualityStore } from "./qualityStore";
+
+const mockInvoke = invoke as unknown as ReturnType<typeof vi.fn>;
+
+const mockSettings: QualitySettings = {
+  caps: { 1: { max_height: 720, max_bitrate: null } },
+  bandwidth_budget: 20000000,
+};
+
+describe("useQualityStore", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+    useQualityStore.setState({ settings: { caps: {}, bandwidth_budget: null } });
+  });
+
+  it("should load the saved settings", async () => {
+    mockInvoke.mockResolvedValue(mockSettings);
+
+    await useQualityStore.getState().loadQualitySettings();
+
+    expect(mockInvoke).toHaveBeenCalledWith("get_quality_settings");
+    expect(useQualityStore.getState().settings).toEqual(mockSettings);
+  });
+
+  it("should keep the defaults when loading fails", async () => {
+    const consoleSpy = vi.spyOn(console, "error").mockImplementation(() => {});
+    mockInvoke.mockRejectedValue(new Error("Storage error"));
+
+    await useQualityStore.getState().loadQualitySettings();
+
+    expect(useQualityStore.getState().settings).toEqual({ caps: {}, bandwidth_budget: null });
+    consoleSpy.mockRestore();
+  });
+
+  it("should cap a viewport", async () => {
+    mockInvoke.mockResolvedValue(mockSettings);
+
+    await useQualityStore.getState().setViewportQuality(1, { max_height: 720, max_bitrate: null });
+
+    expect(mockInvoke).toHaveBeenCalledWith("set_viewport_quality", {
+      viewportIndex: 1,
+      cap: { max_height: 720, max_bitrate: null },
+    });
+    expect(useQualityStore.getState().settings).toEqual(mockSettings);
+  });
+
+  it("should set the bandwidth budget", async () => {
+    mockInvoke.mockResolvedValue(mockSettings);
+
+    await useQualityStore.getState().setBandwidthBudget(20000000);
+
+    expect(mockInvoke).toHaveBeenCalledWith("set_bandwidth_budget", {
+      bitsPerSecond: 20000000,
+    });
+    expect(useQualityStore.getState().settings.bandwidth_budget).toBe(20000000);
+  });
+
+  it("should report the viewport with audio", async () => {
+    mockInvoke.mockResolvedValue(undefined);
+
+    await useQualityStore.getState().setAudioViewport(2);
+
+    expect(mockInvoke).toHaveBeenCalledWith("set_audio_viewport", { viewportIndex: 2 });
+  });
+});
@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+import { create } from "zustand";
+
+import { QualityCap, QualitySettings } from "../types";
+
+interface QualityStore {
+  settings: QualitySettings;
+  loadQualitySettings: () => Promise<void>;
+  // Players pick up new limits the next time they load their stream
+  setViewportQuality: (viewportIndex: number, cap: QualityCap) => Promise<void>;
+  setBandwidthBudget: (bitsPerSecond: number | null) => Promise<void>;
+  // The viewport with audio gets the largest share of the budget
+  setAudioViewport: (viewportIndex: number) => Promise<void>;
+}
+
+export const useQualityStore = create<QualityStore>((set) => ({
+  settings: { caps: {}, bandwidth_budget: null },
+
+  loadQualitySettings: async () => {
+    try {
+      set({ settings: await invoke<QualitySettings>("get_quality_settings") });
+    } catch (error) {
+      console.error("Failed to load quality settings:", error);
+    }
+  },
+
+  setViewportQuality: async (viewportIndex: number, cap: QualityCap) => {
+    const settings = await invoke<QualitySettings>("set_viewport_quality", {
+      viewportIndex,
+      cap,
+    });
+    set({ settings });
+  },
+
+  setBandwidthBudget: async (bitsPerSecond: number | null) => {
+    const settings = await invoke<QualitySettings>("set_bandwidth_budget", { bitsPerSecond });
+    set({ settings });
+  },
+
+  setAudioViewport: async (viewportIndex: number) => {
+    try {
+      await invoke("set_audio_viewport", { viewportIndex });
+    } catch (error) {
+      console.error("Failed to set the audio viewport:", error);
+    }
+  },
+}));
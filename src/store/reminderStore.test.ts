@@ -0,0 +1,80 @@
+import { invoke } from "@tauri-apps/api/core";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+import { OlympicStream, Reminder } from "../types";
+import { useReminderStore } from "./reminderStore";
+
+const mockInvoke = invoke as unknown as ReturnType<typeof vi.fn>;
+
+const mockStream: OlympicStream = {
+  id: "stream-1",
+  title: "Canada vs. Sweden",
+  description: "Women's hockey",
+  sport: "Hockey",
+  status: "upcoming",
+  start_time: "2026-02-11T14:00:00Z",
+  end_time: "2026-02-11T17:00:00Z",
+  thumbnail_url: "https://example.com/thumb1.jpg",
+  stream_url: "https://example.com/stream1.m3u8",
+  requires_auth: true,
+  is_premium: false,
+  vod_enabled: false,
+  section: "olympics",
+};
+
+const mockReminder: Reminder = {
+  stream_id: "stream-1",
+  title: "Canada vs. Sweden",
+  sport: "Hockey",
+  start_time: "2026-02-11T14:00:00Z",
+  lead_minutes: 10,
+  notified: false,
+};
+
+describe("useReminderStore", () => {
+  beforeEach(() => {
+    mockInvoke.mockReset();
+    useReminderStore.setState({ reminders: [] });
+  });
+
+  it("should load the saved reminders", async () => {
+    mockInvoke.mockResolvedValue([mockReminder]);
+
+    await useReminderStore.getState().loadReminders();
+
+    expect(mockInvoke).toHaveBeenCalledWith("list_reminders");
+    expect(useReminderStore.getState().reminders).toEqual([mockReminder]);
+  });
+
+  it("should keep the current reminders when loading fails", async () => {
+    vi.spyOn(console, "error").mockImplementation(() => {});
+    useReminderStore.setState({ reminders: [mockReminder] });
+    mockInvoke.mockRejectedValue(new Error("Storage error"));
+
+    await useReminderStore.getState().loadReminders();
+
+    expect(useReminderStore.getState().reminders).toEqual([mockReminder]);
+  });
+
+  it("should add a reminder with the default lead time", async () => {
+    mockInvoke.mockResolvedValue([mockReminder]);
+
+    await useReminderStore.getState().addReminder(mockStream);
+
+    expect(mockInvoke).toHaveBeenCalledWith("add_reminder", {
+      stream: mockStream,
+      leadMinutes: null,
+    });
+    expect(useReminderStore.getState().hasReminder("stream-1")).toBe(true);
+  });
+
+  it("should cancel a reminder", async () => {
+    useReminderStore.setState({ reminders: [mockReminder] });
+    mockInvoke.mockResolvedValue([]);
+
+    await useReminderStore.getState().cancelReminder("stream-1");
+
+    expect(mockInvoke).toHaveBeenCalledWith("cancel_reminder", { streamId: "stream-1" });
+    expect(useReminderStore.getState().hasReminder("stream-1")).toBe(false);
+  });
+});
@@ -0,0 +1,47 @@
+import { invoke } from "@tauri-apps/api/core";
+import { create } from "zustand";
+
+import { OlympicStream, Reminder } from "../types";
+
+interface ReminderStore {
+  reminders: Reminder[];
+  loadReminders: () => Promise<void>;
+  // The backend reports reminders it completed or rescheduled
+  setReminders: (reminders: Reminder[]) => void;
+  addReminder: (stream: OlympicStream, leadMinutes?: number) => Promise<void>;
+  cancelReminder: (streamId: string) => Promise<void>;
+  hasReminder: (streamId: string) => boolean;
+}
+
+export const useReminderStore = create<ReminderStore>((set, get) => ({
+  reminders: [],
+
+  loadReminders: async () => {
+    try {
+      set({ reminders: await invoke<Reminder[]>("list_reminders") });
+    } catch (error) {
+      console.error("Failed to load reminders:", error);
+    }
+  },
+
+  setReminders: (reminders: Reminder[]) => {
+    set({ reminders });
+  },
+
+  addReminder: async (stream: OlympicStream, leadMinutes?: number) => {
+    const reminders = await invoke<Reminder[]>("add_reminder", {
+      stream,
+      leadMinutes: leadMinutes ?? null,
+    });
+    set({ reminders });
+  },
+
+  cancelReminder: async (streamId: string) => {
+    const reminders = await invoke<Reminder[]>("cancel_reminder", { streamId });
+    set({ reminders });
+  },
+
+  hasReminder: (streamId: string) => {
+    return get().reminders.some((r) => r.stream_id === streamId);
+  },
+}));
@@ -1,7 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
 
-import { OlympicStream, StreamManifest } from "../types";
+import {
+  CatalogSection,
+  OlympicStream,
+  Schedule,
+  SearchResult,
+  StreamCatalog,
+  StreamManifest,
+} from "../types";
 import { useStreamStore } from "./streamStore";
 
 const mockInvoke = invoke as unknown as ReturnType<typeof vi.fn>;
@@ -19,6 +26,8 @@ const mockStreams: OlympicStream[] = [
     stream_url: "https://example.com/stream1.m3u8",
     requires_auth: true,
     is_premium: false,
+    vod_enabled: false,
+    section: "olympics",
   },
   {
     id: "stream-2",
@@ -32,11 +41,21 @@ const mockStreams: OlympicStream[] = [
     stream_url: "https://example.com/stream2.m3u8",
     requires_auth: false,
     is_premium: true,
+    vod_enabled: false,
+    section: "olympics",
   },
 ];
 
+const mockCatalog: StreamCatalog = {
+  streams: mockStreams,
+  stale: false,
+  fetched_at: Date.parse("2026-02-11T10:30:00Z") / 1000,
+  section_errors: [],
+};
+
 const mockManifest: StreamManifest = {
-  url: "https://example.com/stream1.m3u8",
+  url: "http://127.0.0.1:49152/0123456789abcdef0123456789abcdef/master.m3u8",
+  source_url: "https://example.com/stream1.m3u8",
   error_code: 0,
   message: null,
   bitrates: [
@@ -53,6 +72,25 @@ const mockManifest: StreamManifest = {
       lines: "720p",
     },
   ],
+  playlist: {
+    variants: [
+      {
+        uri: "https://example.com/1080p/index.m3u8",
+        bandwidth: 5000000,
+        average_bandwidth: null,
+        resolution: { width: 1920, height: 1080 },
+        codecs: ["avc1.640028", "mp4a.40.2"],
+        frame_rate: 59.94,
+        audio_group: null,
+        subtitles_group: null,
+        closed_captions_group: null,
+      },
+    ],
+    audio: [],
+    subtitles: [],
+    closed_captions: [],
+    iframe_playlists: [],
+  },
 };
 
 describe("useStreamStore", () => {
@@ -63,6 +101,8 @@ describe("useStreamStore", () => {
       isLoading: false,
       error: null,
       lastUpdated: null,
+      isStale: false,
+      failedSections: [],
     });
   });
 
@@ -72,6 +112,8 @@ describe("useStreamStore", () => {
       isLoading: false,
       error: null,
       lastUpdated: null,
+      isStale: false,
+      failedSections: [],
     });
   });
 
@@ -82,32 +124,73 @@ describe("useStreamStore", () => {
         isLoading: false,
         error: null,
         lastUpdated: null,
+        isStale: false,
+        failedSections: [],
         fetchStreams: expect.any(Function),
+        applyCatalogEvent: expect.any(Function),
         getStreamById: expect.any(Function),
+        searchStreams: expect.any(Function),
+        getSchedule: expect.any(Function),
+        exportCalendar: expect.any(Function),
+        takeStreamLink: expect.any(Function),
+        listCatalogSections: expect.any(Function),
+        setCatalogSections: expect.any(Function),
         getStreamManifest: expect.any(Function),
+        closeStream: expect.any(Function),
+        getStreamTraffic: expect.any(Function),
       });
     });
   });
 
   describe("fetchStreams", () => {
     it("should fetch streams successfully", async () => {
-      mockInvoke.mockResolvedValue(mockStreams);
+      mockInvoke.mockResolvedValue(mockCatalog);
 
-      await useStreamStore.getState().fetchStreams({ session_id: "test123" });
+      await useStreamStore.getState().fetchStreams();
 
       const state = useStreamStore.getState();
       expect(state.streams).toEqual(mockStreams);
       expect(state.isLoading).toBe(false);
       expect(state.error).toBeNull();
-      expect(state.lastUpdated).toBeInstanceOf(Date);
+      expect(state.lastUpdated).toEqual(new Date("2026-02-11T10:30:00Z"));
+      expect(state.isStale).toBe(false);
+    });
+
+    it("should flag a stale catalog", async () => {
+      mockInvoke.mockResolvedValue({ ...mockCatalog, stale: true });
+
+      await useStreamStore.getState().fetchStreams();
+
+      const state = useStreamStore.getState();
+      expect(state.streams).toEqual(mockStreams);
+      expect(state.isStale).toBe(true);
+    });
+
+    it("should keep the streams of a catalog with failed sections", async () => {
+      mockInvoke.mockResolvedValue({
+        ...mockCatalog,
+        section_errors: [
+          {
+            section: "paralympics",
+            error: { code: "CATALOG_UNAVAILABLE", message: "Catalog unavailable", status: 503 },
+          },
+        ],
+      });
+
+      await useStreamStore.getState().fetchStreams();
+
+      const state = useStreamStore.getState();
+      expect(state.streams).toEqual(mockStreams);
+      expect(state.isStale).toBe(false);
+      expect(state.failedSections).toEqual(["paralympics"]);
     });
 
     it("should set loading state when fetching streams", async () => {
       mockInvoke.mockImplementation(
-        () => new Promise((resolve) => setTimeout(() => resolve(mockStreams), 100)),
+        () => new Promise((resolve) => setTimeout(() => resolve(mockCatalog), 100)),
       );
 
-      const promise = useStreamStore.getState().fetchStreams({});
+      const promise = useStreamStore.getState().fetchStreams();
       expect(useStreamStore.getState().isLoading).toBe(true);
       await promise;
       expect(useStreamStore.getState().isLoading).toBe(false);
@@ -117,7 +200,7 @@ describe("useStreamStore", () => {
       const error = new Error("Network error");
       mockInvoke.mockRejectedValue(error);
 
-      await useStreamStore.getState().fetchStreams({});
+      await useStreamStore.getState().fetchStreams();
 
       const state = useStreamStore.getState();
       expect(state.streams).toEqual([]);
@@ -129,13 +212,47 @@ describe("useStreamStore", () => {
     it("should handle non-Error errors", async () => {
       mockInvoke.mockRejectedValue("String error");
 
-      await useStreamStore.getState().fetchStreams({});
+      await useStreamStore.getState().fetchStreams();
 
       const state = useStreamStore.getState();
       expect(state.error).toBe("Failed to fetch streams");
     });
   });
 
+  describe("applyCatalogEvent", () => {
+    beforeEach(() => {
+      useStreamStore.setState({ streams: mockStreams });
+    });
+
+    it("should append an added stream", () => {
+      const added = { ...mockStreams[0], id: "stream-3", title: "Stream 3" };
+
+      useStreamStore.getState().applyCatalogEvent("stream-added", added);
+
+      expect(useStreamStore.getState().streams.map((s) => s.id)).toEqual([
+        "stream-1",
+        "stream-2",
+        "stream-3",
+      ]);
+    });
+
+    it("should drop a removed stream", () => {
+      useStreamStore.getState().applyCatalogEvent("stream-removed", mockStreams[0]);
+
+      expect(useStreamStore.getState().streams).toEqual([mockStreams[1]]);
+    });
+
+    it("should update a changed stream in place", () => {
+      const ended = { ...mockStreams[0], status: "ended" as const };
+
+      useStreamStore.getState().applyCatalogEvent("stream-ended", ended);
+
+      const state = useStreamStore.getState();
+      expect(state.streams).toEqual([ended, mockStreams[1]]);
+      expect(state.lastUpdated).toBeInstanceOf(Date);
+    });
+  });
+
   describe("getStreamById", () => {
     beforeEach(() => {
       useStreamStore.setState({ streams: mockStreams });
@@ -158,6 +275,100 @@ describe("useStreamStore", () => {
     });
   });
 
+  describe("searchStreams", () => {
+    it("should pass the query to the backend", async () => {
+      const results: SearchResult[] = [
+        {
+          stream: mockStreams[0],
+          score: 4,
+          highlights: [{ field: "title", start: 0, end: 6 }],
+        },
+      ];
+      mockInvoke.mockResolvedValue(results);
+
+      const query = { text: "stream", statuses: ["live" as const], sort: "relevance" as const };
+      const found = await useStreamStore.getState().searchStreams(query);
+
+      expect(mockInvoke).toHaveBeenCalledWith("search_streams", { query });
+      expect(found).toEqual(results);
+    });
+  });
+
+  describe("getSchedule", () => {
+    it("should request the schedule in the given time zone", async () => {
+      const schedule: Schedule = {
+        time_zone: "America/Toronto",
+        now: "2026-02-11T05:30:00-05:00",
+        days: [],
+        unscheduled: [],
+      };
+      mockInvoke.mockResolvedValue(schedule);
+
+      const result = await useStreamStore.getState().getSchedule("America/Toronto");
+
+      expect(mockInvoke).toHaveBeenCalledWith("get_schedule", { timeZone: "America/Toronto" });
+      expect(result).toEqual(schedule);
+    });
+
+    it("should default to the system time zone", async () => {
+      mockInvoke.mockResolvedValue({});
+
+      await useStreamStore.getState().getSchedule();
+
+      expect(mockInvoke).toHaveBeenCalledWith("get_schedule", {
+        timeZone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+      });
+    });
+  });
+
+  describe("exportCalendar", () => {
+    it("should pass the streams to export", async () => {
+      mockInvoke.mockResolvedValue("/home/user/olympics.ics");
+
+      const path = await useStreamStore.getState().exportCalendar(mockStreams);
+
+      expect(mockInvoke).toHaveBeenCalledWith("export_calendar", { streams: mockStreams });
+      expect(path).toBe("/home/user/olympics.ics");
+    });
+  });
+
+  describe("takeStreamLink", () => {
+    it("should resolve to the stream the app was launched with", async () => {
+      mockInvoke.mockResolvedValue(mockStreams[0]);
+
+      const stream = await useStreamStore.getState().takeStreamLink();
+
+      expect(mockInvoke).toHaveBeenCalledWith("take_stream_link");
+      expect(stream).toEqual(mockStreams[0]);
+    });
+  });
+
+  describe("catalog sections", () => {
+    const sections: CatalogSection[] = [
+      { slug: "olympics", name: "Olympics", enabled: true },
+      { slug: "paralympics", name: "Paralympics", enabled: true },
+    ];
+
+    it("should list the available sections", async () => {
+      mockInvoke.mockResolvedValue(sections);
+
+      const listed = await useStreamStore.getState().listCatalogSections();
+
+      expect(mockInvoke).toHaveBeenCalledWith("list_catalog_sections");
+      expect(listed).toEqual(sections);
+    });
+
+    it("should pass the chosen section slugs", async () => {
+      mockInvoke.mockResolvedValue(sections);
+
+      await useStreamStore.getState().setCatalogSections(["olympics", "paralympics"]);
+
+      expect(mockInvoke).toHaveBeenCalledWith("set_catalog_sections", {
+        slugs: ["olympics", "paralympics"],
+      });
+    });
+  });
+
   describe("getStreamManifest", () => {
     it("should fetch stream manifest successfully", async () => {
       mockInvoke.mockResolvedValue(mockManifest);
@@ -177,7 +388,44 @@ describe("useStreamStore", () => {
 
       expect(mockInvoke).toHaveBeenCalledWith("get_stream_manifest", {
         streamUrl,
+        viewportIndex: null,
+      });
+    });
+
+    it("should pass the viewport index", async () => {
+      mockInvoke.mockResolvedValue(mockManifest);
+
+      const streamUrl = "https://example.com/stream1.m3u8";
+      await useStreamStore.getState().getStreamManifest(streamUrl, 2);
+
+      expect(mockInvoke).toHaveBeenCalledWith("get_stream_manifest", {
+        streamUrl,
+        viewportIndex: 2,
       });
     });
   });
+
+  describe("closeStream", () => {
+    it("should close the proxied stream by its url", async () => {
+      mockInvoke.mockResolvedValue(undefined);
+
+      await useStreamStore.getState().closeStream("http://127.0.0.1:4000/abc/master.m3u8");
+
+      expect(mockInvoke).toHaveBeenCalledWith("close_stream", {
+        url: "http://127.0.0.1:4000/abc/master.m3u8",
+      });
+    });
+  });
+
+  describe("getStreamTraffic", () => {
+    it("should return the traffic per viewport", async () => {
+      const traffic = [{ viewport_index: 0, bytes: 1048576, requests: 12 }];
+      mockInvoke.mockResolvedValue(traffic);
+
+      const result = await useStreamStore.getState().getStreamTraffic();
+
+      expect(mockInvoke).toHaveBeenCalledWith("get_stream_traffic");
+      expect(result).toEqual(traffic);
+    });
+  });
 });
@@ -1,16 +1,44 @@
 import { invoke } from "@tauri-apps/api/core";
 import { create } from "zustand";
 
-import { OlympicStream, StreamManifest } from "../types";
+import { errorMessage } from "../lib/errors";
+import {
+  CatalogEvent,
+  CatalogSection,
+  OlympicStream,
+  Schedule,
+  SearchResult,
+  StreamCatalog,
+  StreamManifest,
+  StreamQuery,
+  ViewportTraffic,
+} from "../types";
 
 interface StreamStore {
   streams: OlympicStream[];
   isLoading: boolean;
   error: string | null;
   lastUpdated: Date | null;
-  fetchStreams: (cookies: Record<string, string>) => Promise<void>;
+  // Streams come from the backend's saved catalog because CBC was unreachable
+  isStale: boolean;
+  // Slugs of the sections the last fetch couldn't reach
+  failedSections: string[];
+  fetchStreams: () => Promise<void>;
+  applyCatalogEvent: (event: CatalogEvent, stream: OlympicStream) => void;
   getStreamById: (id: string) => OlympicStream | undefined;
-  getStreamManifest: (streamUrl: string) => Promise<StreamManifest>;
+  searchStreams: (query: StreamQuery) => Promise<SearchResult[]>;
+  getSchedule: (timeZone?: string) => Promise<Schedule>;
+  // Resolves to the saved file, or null if the user cancelled
+  exportCalendar: (streams: OlympicStream[]) => Promise<string | null>;
+  // The stream behind the link the app was launched with, the first time it's asked for
+  takeStreamLink: () => Promise<OlympicStream | null>;
+  listCatalogSections: () => Promise<CatalogSection[]>;
+  // The backend refreshes the catalog and reports the changes as catalog events
+  setCatalogSections: (slugs: string[]) => Promise<CatalogSection[]>;
+  getStreamManifest: (streamUrl: string, viewportIndex?: number) => Promise<StreamManifest>;
+  // Releases the proxied stream behind a manifest's url once its player is done with it
+  closeStream: (url: string) => Promise<void>;
+  getStreamTraffic: () => Promise<ViewportTraffic[]>;
 }
 
 export const useStreamStore = create<StreamStore>((set, get) => ({
@@ -18,34 +46,88 @@ export const useStreamStore = create<StreamStore>((set, get) => ({
   isLoading: false,
   error: null,
   lastUpdated: null,
+  isStale: false,
+  failedSections: [],
 
-  fetchStreams: async (cookies: Record<string, string>) => {
+  fetchStreams: async () => {
     set({ isLoading: true, error: null });
     try {
-      const streams = await invoke<OlympicStream[]>("fetch_olympic_streams", {
-        cookies,
-      });
+      const catalog = await invoke<StreamCatalog>("fetch_streams");
       set({
-        streams,
-        lastUpdated: new Date(),
+        streams: catalog.streams,
+        lastUpdated: new Date(catalog.fetched_at * 1000),
+        isStale: catalog.stale,
+        failedSections: catalog.section_errors.map((e) => e.section),
         isLoading: false,
       });
     } catch (error) {
       set({
-        error: error instanceof Error ? error.message : "Failed to fetch streams",
+        error: errorMessage(error, "Failed to fetch streams"),
         isLoading: false,
       });
     }
   },
 
+  applyCatalogEvent: (event: CatalogEvent, stream: OlympicStream) => {
+    const others = get().streams.filter((s) => s.id !== stream.id);
+    if (event === "stream-removed") {
+      set({ streams: others, lastUpdated: new Date(), isStale: false });
+      return;
+    }
+
+    // Keep a changed stream where it was; new ones go at the end
+    const index = get().streams.findIndex((s) => s.id === stream.id);
+    const streams =
+      index === -1
+        ? [...others, stream]
+        : [...others.slice(0, index), stream, ...others.slice(index)];
+    set({ streams, lastUpdated: new Date(), isStale: false });
+  },
+
   getStreamById: (id: string) => {
     return get().streams.find((s) => s.id === id);
   },
 
-  getStreamManifest: async (streamUrl: string) => {
+  searchStreams: async (query: StreamQuery) => {
+    return invoke<SearchResult[]>("search_streams", { query });
+  },
+
+  getSchedule: async (timeZone?: string) => {
+    return invoke<Schedule>("get_schedule", {
+      timeZone: timeZone ?? Intl.DateTimeFormat().resolvedOptions().timeZone,
+    });
+  },
+
+  exportCalendar: async (streams: OlympicStream[]) => {
+    return invoke<string | null>("export_calendar", { streams });
+  },
+
+  takeStreamLink: async () => {
+    return invoke<OlympicStream | null>("take_stream_link");
+  },
+
+  listCatalogSections: async () => {
+    return invoke<CatalogSection[]>("list_catalog_sections");
+  },
+
+  setCatalogSections: async (slugs: string[]) => {
+    return invoke<CatalogSection[]>("set_catalog_sections", { slugs });
+  },
+
+  getStreamManifest: async (streamUrl: string, viewportIndex?: number) => {
+    // The backend plays a viewport with the account pinned to it, if any
     const manifest = await invoke<StreamManifest>("get_stream_manifest", {
       streamUrl,
+      viewportIndex: viewportIndex ?? null,
     });
     return manifest;
   },
+
+  closeStream: async (url: string) => {
+    return invoke<void>("close_stream", { url });
+  },
+
+  getStreamTraffic: async () => {
+    return invoke<ViewportTraffic[]>("get_stream_traffic");
+  },
 }));
@@ -1,13 +1,76 @@
 // Authentication types
-export interface AuthSession {
-  cookies: Record<string, string>;
+// Cookies stay in the Rust backend; the webview only ever sees this summary.
+export interface SessionSummary {
+  signed_in: boolean;
   user_id: string | null;
+  display_name: string | null;
+  expires_at: number | null;
+}
+
+export interface AccountSummary {
+  name: string;
+  user_id: string | null;
+  display_name: string | null;
+  tier: "Free" | "Member" | "Premium" | null;
   expires_at: number;
+  active: boolean;
+}
+
+// Error returned by every backend command
+export type LoonieErrorCode =
+  | "NOT_AUTHENTICATED"
+  | "SESSION_EXPIRED"
+  | "GEO_BLOCKED"
+  | "CONCURRENT_STREAM_LIMIT"
+  | "PREMIUM_REQUIRED"
+  | "STREAM_UNAVAILABLE"
+  | "CATALOG_UNAVAILABLE"
+  | "INVALID_STREAM_URL"
+  | "NETWORK"
+  | "PARSE"
+  | "UNKNOWN_ACCOUNT"
+  | "INVALID_TIME_ZONE"
+  | "INVALID_CATALOG_SECTION"
+  | "REMINDER_NOT_UPCOMING"
+  | "IMPORT_FAILED"
+  | "STORAGE"
+  | "INTERNAL";
+
+export interface LoonieError {
+  code: LoonieErrorCode;
+  message: string;
+  // Variant fields, e.g. `status`, `account`, `error_code`
+  [field: string]: unknown;
+  // Set when the validation API refused a stream
+  explanation?: { en: string; fr: string };
+  hint?: RecoveryHint;
 }
 
+// Why the validation API refused a stream
+export type ValidationFailure =
+  | "geo_blocked"
+  | "login_required"
+  | "not_entitled"
+  | "concurrent_stream_limit"
+  | "not_yet_available"
+  | "expired"
+  | "unknown";
+
+export interface RecoveryHint {
+  retry: boolean;
+  relogin: boolean;
+  upgrade: boolean;
+}
+
+// Where to import CBC cookies from when the sign-in window can't be used
+export type ImportSource =
+  | { kind: "cookies_txt"; path: string }
+  | { kind: "firefox"; profile: string }
+  | { kind: "chromium"; profile: string };
+
 export interface AuthState {
   isAuthenticated: boolean;
-  session: AuthSession | null;
+  session: SessionSummary | null;
 }
 
 // Stream types
@@ -25,6 +88,109 @@ export interface OlympicStream {
   stream_url: string;
   requires_auth: boolean;
   is_premium: boolean;
+  vod_enabled: boolean;
+  // Slug of the GEM catalog section the stream is listed in, e.g. "olympics"
+  section: string;
+}
+
+export interface CatalogSection {
+  slug: string;
+  name: string;
+  enabled: boolean;
+}
+
+// A section the backend couldn't fetch; its streams are from the saved catalog
+export interface SectionError {
+  section: string;
+  error: LoonieError;
+}
+
+// Filters for `search_streams`; anything left out matches everything
+export interface StreamQuery {
+  text?: string;
+  sport?: string;
+  statuses?: StreamStatus[];
+  // Local calendar days, "YYYY-MM-DD", inclusive
+  from_date?: string;
+  to_date?: string;
+  premium?: boolean;
+  sort?: "start_time" | "relevance";
+}
+
+// Offsets are UTF-16 code units into the stream's title or description
+export interface Highlight {
+  field: "title" | "description";
+  start: number;
+  end: number;
+}
+
+export interface SearchResult {
+  stream: OlympicStream;
+  score: number;
+  highlights: Highlight[];
+}
+
+// Times are RFC 3339 with the offset of the requested time zone
+export interface ScheduleEvent {
+  stream: OlympicStream;
+  local_start: string;
+  local_end: string | null;
+  is_now: boolean;
+}
+
+export interface SportSchedule {
+  sport: string;
+  events: ScheduleEvent[];
+}
+
+// A stretch between a day's events with nothing on air
+export interface ScheduleGap {
+  start: string;
+  end: string;
+  minutes: number;
+  is_now: boolean;
+}
+
+export interface ScheduleDay {
+  date: string;
+  is_today: boolean;
+  sports: SportSchedule[];
+  gaps: ScheduleGap[];
+}
+
+export interface Schedule {
+  time_zone: string;
+  now: string;
+  days: ScheduleDay[];
+  // Streams whose start time couldn't be read
+  unscheduled: OlympicStream[];
+}
+
+// A starred upcoming stream; the backend notifies before it starts and when it goes live
+export interface Reminder {
+  stream_id: string;
+  title: string;
+  sport: string;
+  start_time: string;
+  lead_minutes: number;
+  notified: boolean;
+}
+
+// Emitted by the backend catalog watcher with the affected stream as payload
+export type CatalogEvent =
+  | "stream-added"
+  | "stream-removed"
+  | "stream-went-live"
+  | "stream-ended"
+  | "stream-replay-available";
+
+// `stale` is set when the backend couldn't reach CBC and served its saved copy
+export interface StreamCatalog {
+  streams: OlympicStream[];
+  stale: boolean;
+  // Unix seconds
+  fetched_at: number;
+  section_errors: SectionError[];
 }
 
 // Viewport types
@@ -51,11 +217,76 @@ export interface BitrateInfo {
   lines: string;
 }
 
+export interface Resolution {
+  width: number;
+  height: number;
+}
+
+// Master playlist types (URIs are absolute)
+export interface Variant {
+  uri: string;
+  bandwidth: number; // peak bits per second
+  average_bandwidth: number | null;
+  resolution: Resolution | null;
+  codecs: string[];
+  frame_rate: number | null;
+  audio_group: string | null;
+  subtitles_group: string | null;
+  closed_captions_group: string | null;
+}
+
+export interface Rendition {
+  group_id: string;
+  name: string;
+  language: string | null;
+  uri: string | null;
+  instream_id: string | null;
+  channels: string | null;
+  is_default: boolean;
+  autoselect: boolean;
+  forced: boolean;
+}
+
+export interface IFramePlaylist {
+  uri: string;
+  bandwidth: number;
+  resolution: Resolution | null;
+  codecs: string[];
+}
+
+export interface MasterPlaylist {
+  variants: Variant[];
+  audio: Rendition[];
+  subtitles: Rendition[];
+  closed_captions: Rendition[];
+  iframe_playlists: IFramePlaylist[];
+}
+
 export interface StreamManifest {
-  url: string;
+  url: string; // through the local stream proxy when it's running
+  source_url: string;
   error_code: number;
   message: string | null;
   bitrates: BitrateInfo[];
+  playlist: MasterPlaylist | null; // null when the playlist couldn't be read
+}
+
+// Bytes a viewport's player has loaded through the stream proxy
+export interface ViewportTraffic {
+  viewport_index: number;
+  bytes: number;
+  requests: number;
+}
+
+// Highest quality a viewport may play; null leaves that side open
+export interface QualityCap {
+  max_height: number | null;
+  max_bitrate: number | null; // bits per second
+}
+
+export interface QualitySettings {
+  caps: Record<number, QualityCap>; // by viewport index
+  bandwidth_budget: number | null; // bits per second, split across playing viewports
 }
 
 // App settings